@@ -5,13 +5,17 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{ecall0, ecall1};
+use crate::{ecall0, ecall1, extensions::Extension, SbiError};
 
 /// Base extension ID
 pub const EXTENSION_ID: usize = 0x10;
 
 /// SBI specification version implemented by the SBI implementation
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Versions are ordered by their major version number first, then by their
+/// minor version number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SbiSpecVersion {
     /// Major version number
     pub major: usize,
@@ -19,18 +23,41 @@ pub struct SbiSpecVersion {
     pub minor: usize,
 }
 
+impl SbiSpecVersion {
+    /// Decode the raw spec version value returned by the SBI implementation,
+    /// where bits `24..31` contain the major version and bits `0..24` contain
+    /// the minor version. The reserved bit 31 is ignored.
+    #[inline]
+    pub const fn from_raw(value: u32) -> Self {
+        Self {
+            major: ((value >> 24) & 0x7f) as usize,
+            minor: (value & 0xff_ffff) as usize,
+        }
+    }
+
+    /// Encode the version into the raw spec version value, truncating the
+    /// major and minor version numbers to their 7 and 24 bit fields
+    #[inline]
+    pub const fn to_raw(self) -> u32 {
+        (((self.major & 0x7f) << 24) | (self.minor & 0xff_ffff)) as u32
+    }
+
+    /// Whether this version is greater than or equal to `major.minor`
+    pub fn at_least(&self, major: usize, minor: usize) -> bool {
+        *self >= SbiSpecVersion { major, minor }
+    }
+}
+
 /// Retrieve the SBI specification version
 pub fn spec_version() -> SbiSpecVersion {
     let value = unsafe { ecall0(EXTENSION_ID, 0).unwrap() };
-    SbiSpecVersion {
-        major: (value >> 24) & 0x7f,
-        minor: value & 0xff_ffff,
-    }
+    SbiSpecVersion::from_raw(value as u32)
 }
 
 /// SBI implementation name
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SbiImplId {
     BerkeleyBootLoader,
     OpenSbi,
@@ -39,7 +66,12 @@ pub enum SbiImplId {
     RustSbi,
     Diosix,
     Coffer,
-    Other(usize),
+    XenProject,
+    PolarFireHartSoftwareServices,
+    Coreboot,
+    Oreboot,
+    /// An implementation ID not known to this crate
+    Unknown(usize),
 }
 
 impl SbiImplId {
@@ -53,7 +85,11 @@ impl SbiImplId {
             SbiImplId::RustSbi => 4,
             SbiImplId::Diosix => 5,
             SbiImplId::Coffer => 6,
-            SbiImplId::Other(n) => n,
+            SbiImplId::XenProject => 7,
+            SbiImplId::PolarFireHartSoftwareServices => 8,
+            SbiImplId::Coreboot => 9,
+            SbiImplId::Oreboot => 10,
+            SbiImplId::Unknown(n) => n,
         }
     }
 
@@ -66,7 +102,44 @@ impl SbiImplId {
             4 => SbiImplId::RustSbi,
             5 => SbiImplId::Diosix,
             6 => SbiImplId::Coffer,
-            n => SbiImplId::Other(n),
+            7 => SbiImplId::XenProject,
+            8 => SbiImplId::PolarFireHartSoftwareServices,
+            9 => SbiImplId::Coreboot,
+            10 => SbiImplId::Oreboot,
+            n => SbiImplId::Unknown(n),
+        }
+    }
+}
+
+impl From<usize> for SbiImplId {
+    fn from(value: usize) -> Self {
+        Self::from_usize(value)
+    }
+}
+
+impl From<SbiImplId> for usize {
+    fn from(value: SbiImplId) -> Self {
+        value.into_usize()
+    }
+}
+
+impl core::fmt::Display for SbiImplId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SbiImplId::BerkeleyBootLoader => f.write_str("Berkeley Boot Loader (BBL)"),
+            SbiImplId::OpenSbi => f.write_str("OpenSBI"),
+            SbiImplId::Xvisor => f.write_str("Xvisor"),
+            SbiImplId::Kvm => f.write_str("KVM"),
+            SbiImplId::RustSbi => f.write_str("RustSBI"),
+            SbiImplId::Diosix => f.write_str("Diosix"),
+            SbiImplId::Coffer => f.write_str("Coffer"),
+            SbiImplId::XenProject => f.write_str("Xen Project"),
+            SbiImplId::PolarFireHartSoftwareServices => {
+                f.write_str("PolarFire Hart Software Services")
+            }
+            SbiImplId::Coreboot => f.write_str("coreboot"),
+            SbiImplId::Oreboot => f.write_str("oreboot"),
+            SbiImplId::Unknown(n) => write!(f, "unknown implementation ({n:#x})"),
         }
     }
 }
@@ -101,6 +174,16 @@ impl ExtensionAvailability {
             ExtensionAvailability::Unavailable => false,
         }
     }
+
+    /// The raw value returned when probing the extension. Some extensions
+    /// define a meaning for specific non-zero values, which can be interpreted
+    /// using this value.
+    pub fn raw(self) -> isize {
+        match self {
+            ExtensionAvailability::Available(n) => n.get() as isize,
+            ExtensionAvailability::Unavailable => 0,
+        }
+    }
 }
 
 /// Probe the availability of the extension ID `id`
@@ -113,6 +196,126 @@ pub fn probe_extension(id: usize) -> ExtensionAvailability {
     }
 }
 
+/// Probe the availability of the extension `E`, such as
+/// [`extensions::Timer`](crate::extensions::Timer). This is equivalent to
+/// calling [`probe_extension`] with `E::ID`.
+#[inline]
+pub fn probe<E: Extension>() -> ExtensionAvailability {
+    probe_extension(E::ID)
+}
+
+/// Probe the availability of the extension ID `id`, and run `f` only if the
+/// extension is available. Returns `Ok(None)` without running `f` if the
+/// extension is unavailable, otherwise returns the result of `f`.
+///
+/// Every call performs one [`probe_extension`] SBI call before running `f`, so
+/// callers which use an extension repeatedly should probe it once up front
+/// instead.
+///
+/// ### Possible errors
+///
+/// Any error returned by `f`.
+pub fn with_extension<T>(
+    id: usize,
+    f: impl FnOnce() -> Result<T, SbiError>,
+) -> Result<Option<T>, SbiError> {
+    match probe_extension(id) {
+        ExtensionAvailability::Available(_) => f().map(Some),
+        ExtensionAvailability::Unavailable => Ok(None),
+    }
+}
+
+/// A standard SBI extension known to this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExtensionId {
+    /// Base extension
+    Base,
+    /// Timer extension
+    Timer,
+    /// IPI extension
+    Ipi,
+    /// RFENCE extension
+    Rfence,
+    /// Hart State Management extension
+    HartStateManagement,
+    /// System Reset extension
+    SystemReset,
+    /// Performance Monitoring Unit extension
+    PerformanceMonitoringUnit,
+    /// Debug Console extension
+    DebugConsole,
+    /// System Suspend extension
+    SystemSuspend,
+    /// Collaborative Processor Performance Control extension
+    CollaborativeProcessorPerformanceControl,
+    /// Nested Acceleration extension
+    NestedAcceleration,
+    /// Steal-time Accounting extension
+    StealTimeAccounting,
+    /// Firmware Features extension
+    FirmwareFeatures,
+    /// Debug Triggers extension
+    DebugTriggers,
+}
+
+impl ExtensionId {
+    /// Every standard extension known to this crate
+    pub const ALL: [ExtensionId; 14] = [
+        ExtensionId::Base,
+        ExtensionId::Timer,
+        ExtensionId::Ipi,
+        ExtensionId::Rfence,
+        ExtensionId::HartStateManagement,
+        ExtensionId::SystemReset,
+        ExtensionId::PerformanceMonitoringUnit,
+        ExtensionId::DebugConsole,
+        ExtensionId::SystemSuspend,
+        ExtensionId::CollaborativeProcessorPerformanceControl,
+        ExtensionId::NestedAcceleration,
+        ExtensionId::StealTimeAccounting,
+        ExtensionId::FirmwareFeatures,
+        ExtensionId::DebugTriggers,
+    ];
+
+    /// The extension ID value used to probe the extension
+    pub fn into_usize(self) -> usize {
+        use crate::extensions as ext;
+
+        match self {
+            ExtensionId::Base => ext::Base::ID,
+            ExtensionId::Timer => ext::Timer::ID,
+            ExtensionId::Ipi => ext::Ipi::ID,
+            ExtensionId::Rfence => ext::Rfence::ID,
+            ExtensionId::HartStateManagement => ext::HartStateManagement::ID,
+            ExtensionId::SystemReset => ext::SystemReset::ID,
+            ExtensionId::PerformanceMonitoringUnit => ext::PerformanceMonitoringUnit::ID,
+            ExtensionId::DebugConsole => ext::DebugConsole::ID,
+            ExtensionId::SystemSuspend => ext::SystemSuspend::ID,
+            ExtensionId::CollaborativeProcessorPerformanceControl => {
+                ext::CollaborativeProcessorPerformanceControl::ID
+            }
+            ExtensionId::NestedAcceleration => ext::NestedAcceleration::ID,
+            ExtensionId::StealTimeAccounting => ext::StealTimeAccounting::ID,
+            ExtensionId::FirmwareFeatures => ext::FirmwareFeatures::ID,
+            ExtensionId::DebugTriggers => ext::DebugTriggers::ID,
+        }
+    }
+}
+
+impl From<ExtensionId> for usize {
+    fn from(value: ExtensionId) -> Self {
+        value.into_usize()
+    }
+}
+
+/// Probe every standard extension in [`ExtensionId::ALL`], returning whether
+/// each extension is available
+pub fn available_extensions() -> impl Iterator<Item = (ExtensionId, bool)> {
+    ExtensionId::ALL
+        .into_iter()
+        .map(|id| (id, probe_extension(id.into_usize()).is_available()))
+}
+
 /// Retrieve the value of `mvendorid` CSR
 pub fn mvendorid() -> usize {
     unsafe { ecall0(EXTENSION_ID, 4).unwrap() }
@@ -13,6 +13,7 @@ pub const EXTENSION_ID: usize = 0x43505043;
 #[doc(hidden)]
 pub trait CastRegisterValue: Sized + Copy {
     fn cast(from: usize) -> Self;
+    fn from_hi_lo(hi: usize, lo: usize) -> Self;
     fn reverse_cast(self) -> usize;
     fn hi_lo(self) -> (usize, usize);
 }
@@ -22,6 +23,10 @@ impl CastRegisterValue for u64 {
         from as u64
     }
 
+    fn from_hi_lo(hi: usize, lo: usize) -> Self {
+        ((hi as u64) << 32) | (lo as u64 & 0xFFFF_FFFF)
+    }
+
     fn reverse_cast(self) -> usize {
         self as usize
     }
@@ -36,6 +41,10 @@ impl CastRegisterValue for u32 {
         from as u32
     }
 
+    fn from_hi_lo(_: usize, lo: usize) -> Self {
+        lo as u32
+    }
+
     fn reverse_cast(self) -> usize {
         self as usize
     }
@@ -60,7 +69,8 @@ pub trait Writable: Register {}
 
 /// CPPC registers defined by the SBI specification
 pub mod registers {
-    use super::{Readable, Register, Writable};
+    use super::{CastRegisterValue, Readable, Register, Writable};
+    use core::marker::PhantomData;
 
     /// ACPI Specification 6.5; 8.4.6.1.1 Highest Performance
     ///
@@ -569,6 +579,190 @@ pub mod registers {
         const ID: u32 = 0x80000000;
         type Width = u32;
     }
+
+    /// A platform-specific register with a custom register ID and value width,
+    /// for registers not otherwise defined in this module. The register ID must
+    /// be within the platform-specific range `0x80000001..=0xFFFFFFFF`, which
+    /// is checked at compile time by [`PlatformSpecific::new`], so that the
+    /// standard registers keep their read/write restrictions. Whether the
+    /// register can actually be read from or written to is determined by the
+    /// platform, so [`super::probe_register`] should be used to check that the
+    /// register is implemented before accessing it.
+    ///
+    /// ```rust,ignore
+    /// type VendorRegister = PlatformSpecific<0x80001000, u32>;
+    /// let value = read_register(VendorRegister::new())?;
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct PlatformSpecific<const ID: u32, W>(PhantomData<W>);
+
+    impl<const ID: u32, W> PlatformSpecific<ID, W> {
+        /// Create a new [`PlatformSpecific`] register value. Fails to compile if
+        /// `ID` is not a platform-specific register ID.
+        pub const fn new() -> Self {
+            const {
+                assert!(
+                    ID > 0x8000_0000,
+                    "platform-specific CPPC register IDs must be within 0x80000001..=0xFFFFFFFF"
+                )
+            };
+            Self(PhantomData)
+        }
+    }
+
+    impl<const ID: u32, W> Default for PlatformSpecific<ID, W> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<const ID: u32, W: CastRegisterValue> Readable for PlatformSpecific<ID, W> {}
+    impl<const ID: u32, W: CastRegisterValue> Writable for PlatformSpecific<ID, W> {}
+    impl<const ID: u32, W: CastRegisterValue> Register for PlatformSpecific<ID, W> {
+        const ID: u32 = ID;
+        type Width = W;
+    }
+}
+
+/// A performance level on the abstract, unitless performance scale used by the
+/// performance registers, such as [`registers::HighestPerformance`] and
+/// [`registers::DesiredPerformance`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct PerformanceLevel(pub u32);
+
+impl PerformanceLevel {
+    /// Create a new [`PerformanceLevel`]
+    pub const fn new(level: u32) -> Self {
+        Self(level)
+    }
+
+    /// The raw performance level value
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for PerformanceLevel {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<PerformanceLevel> for u32 {
+    fn from(value: PerformanceLevel) -> Self {
+        value.0
+    }
+}
+
+/// The range of performance levels advertised by the platform, from
+/// [`registers::LowestPerformance`] to [`registers::HighestPerformance`],
+/// inclusive. Values written to [`registers::DesiredPerformance`],
+/// [`registers::MinimumPerformance`], and [`registers::MaximumPerformance`]
+/// should fall within this range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerformanceRange {
+    /// The lowest performance level of the platform
+    pub lowest: PerformanceLevel,
+    /// The highest performance level of the platform
+    pub highest: PerformanceLevel,
+}
+
+impl PerformanceRange {
+    /// Create a new [`PerformanceRange`] from the lowest and highest
+    /// performance levels
+    pub const fn new(lowest: PerformanceLevel, highest: PerformanceLevel) -> Self {
+        Self { lowest, highest }
+    }
+
+    /// Read the [`registers::LowestPerformance`] and
+    /// [`registers::HighestPerformance`] registers to determine the performance
+    /// range of the platform.
+    ///
+    /// ### Possible errors
+    ///
+    /// See [`read_register`].
+    pub fn read() -> Result<Self, SbiError> {
+        let lowest = read_register(registers::LowestPerformance)?;
+        let highest = read_register(registers::HighestPerformance)?;
+
+        Ok(Self::new(
+            PerformanceLevel(lowest),
+            PerformanceLevel(highest),
+        ))
+    }
+
+    /// Whether the given performance level is within the range
+    pub const fn contains(&self, level: PerformanceLevel) -> bool {
+        self.lowest.0 <= level.0 && level.0 <= self.highest.0
+    }
+
+    /// Clamp the given performance level into the range, so that it can be
+    /// written to [`registers::DesiredPerformance`]
+    pub const fn clamp(&self, level: PerformanceLevel) -> PerformanceLevel {
+        if level.0 < self.lowest.0 {
+            self.lowest
+        } else if level.0 > self.highest.0 {
+            self.highest
+        } else {
+            level
+        }
+    }
+}
+
+/// The decoded value of the [`registers::PerformanceLimited`] register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct PerformanceLimitedStatus(u32);
+
+impl PerformanceLimitedStatus {
+    const DESIRED_EXCURSION: u32 = 1 << 0;
+    const MINIMUM_EXCURSION: u32 = 1 << 1;
+
+    /// Create a [`PerformanceLimitedStatus`] from the raw register value
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// The raw register value, including any reserved bits
+    pub const fn raw(self) -> u32 {
+        self.0
+    }
+
+    /// `Desired_Excursion`: delivered performance has been constrained to less
+    /// than the desired performance (or less than the guaranteed performance,
+    /// if the desired performance is greater than the guaranteed performance)
+    pub const fn desired_excursion(self) -> bool {
+        self.0 & Self::DESIRED_EXCURSION != 0
+    }
+
+    /// `Minimum_Excursion`: delivered performance has been constrained to less
+    /// than the minimum performance
+    pub const fn minimum_excursion(self) -> bool {
+        self.0 & Self::MINIMUM_EXCURSION != 0
+    }
+}
+
+/// Read and decode the [`registers::PerformanceLimited`] register. The bits of
+/// this register are sticky, and remain set until cleared with
+/// [`clear_performance_limited`].
+///
+/// ### Possible errors
+///
+/// See [`read_register`].
+pub fn read_performance_limited() -> Result<PerformanceLimitedStatus, SbiError> {
+    read_register(registers::PerformanceLimited).map(PerformanceLimitedStatus)
+}
+
+/// Clear the sticky bits of the [`registers::PerformanceLimited`] register by
+/// writing zero to it.
+///
+/// ### Possible errors
+///
+/// See [`write_register`].
+#[must_use = "a failed register write leaves the register unchanged"]
+pub fn clear_performance_limited() -> Result<(), SbiError> {
+    write_register(registers::PerformanceLimited, 0)
 }
 
 /// Probe whether the given CPPC register is supported. On success, this
@@ -593,6 +787,56 @@ pub fn probe_register<R: Register>(
     }
 }
 
+/// Probe whether the given CPPC register is supported. On success, this
+/// function returns the decoded width of the register, if the register is
+/// implemented. See [`probe_register`] for more information.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The register ID is a reserved ID.
+///
+/// [`SbiError::FAILED`]: The probe request failed for unspecified or unknown
+///     reasons.
+pub fn probe_register_width<R: Register>(
+    register: R,
+) -> Result<Option<CpccRegisterWidth>, SbiError> {
+    probe_register(register).map(|width| width.map(CpccRegisterWidth::from))
+}
+
+/// The width of an implemented CPPC register, as returned by
+/// [`probe_register_width`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpccRegisterWidth {
+    /// The register is 32 bits wide
+    Bits32,
+    /// The register is 64 bits wide, and requires a subsequent call to
+    /// [`read_register_hi`] to read the full value when `XLEN` is 32
+    Bits64,
+    /// The register has a width other than 32 or 64 bits
+    Other(usize),
+}
+
+impl CpccRegisterWidth {
+    /// The width of the register in bits
+    pub const fn bits(self) -> usize {
+        match self {
+            Self::Bits32 => 32,
+            Self::Bits64 => 64,
+            Self::Other(bits) => bits,
+        }
+    }
+}
+
+impl From<usize> for CpccRegisterWidth {
+    fn from(value: usize) -> Self {
+        match value {
+            32 => Self::Bits32,
+            64 => Self::Bits64,
+            n => Self::Other(n),
+        }
+    }
+}
+
 /// Read the value of a CPPC register. When `XLEN` is 32, this value only
 /// contains the lower 32 bits of the full register value, and a subsequent call
 /// to [`read_register_hi`] is required to read the full value if the register
@@ -615,6 +859,28 @@ pub fn read_register<R: Readable>(
     unsafe { ecall1(R::ID as usize, EXTENSION_ID, 1) }.map(<R::Width as CastRegisterValue>::cast)
 }
 
+/// Probe the given CPPC register and, if it is implemented by the platform,
+/// read its full value, including the upper 32 bits of a 64-bit register when
+/// `XLEN` is 32. Returns `Ok(None)` if the register is not implemented. To read
+/// the same register repeatedly, create a [`RegisterHandle`] instead so that
+/// the register is only probed once.
+///
+/// ### Possible errors
+///
+/// See [`probe_register`] and [`read_register`].
+pub fn probe_and_read<R: Readable>(register: R) -> Result<Option<R::Width>, SbiError> {
+    let Some(width) = probe_register_width(register)? else {
+        return Ok(None);
+    };
+
+    RegisterHandle::<R> {
+        register: core::marker::PhantomData,
+        width,
+    }
+    .read()
+    .map(Some)
+}
+
 /// Read the upper 32 bits of the register value. When `XLEN` >= 64, this
 /// function will always return `0` for valid register IDs.
 ///
@@ -648,10 +914,15 @@ pub fn read_register_hi<R: Readable>(
 /// [`SbiError::FAILED`]: The write request failed for unspecified or unknown
 ///     reasons.
 #[doc(alias = "sbi_cppc_write")]
-pub fn write_register<R: Readable>(
+#[must_use = "a failed register write leaves the register unchanged"]
+pub fn write_register<R: Writable>(
     #[allow(unused_variables)] register: R,
     value: R::Width,
 ) -> Result<(), SbiError> {
+    write_register_value::<R>(value)
+}
+
+fn write_register_value<R: Writable>(value: R::Width) -> Result<(), SbiError> {
     #[cfg(target_arch = "riscv64")]
     unsafe {
         crate::ecall2(
@@ -670,3 +941,77 @@ pub fn write_register<R: Readable>(
 
     Ok(())
 }
+
+/// A handle to a CPPC register which has been probed once up front, caching the
+/// register width so that repeated accesses, such as writes to
+/// [`registers::DesiredPerformance`] in a frequency scaling loop, perform the
+/// minimum number of SBI calls. Reading a 64-bit register when `XLEN` is 32
+/// takes two SBI calls, otherwise every access takes a single SBI call.
+///
+/// Whether the register can be read from or written to is determined by the
+/// [`Readable`] and [`Writable`] traits of the register type.
+#[derive(Debug, Clone, Copy)]
+pub struct RegisterHandle<R: Register> {
+    register: core::marker::PhantomData<R>,
+    width: CpccRegisterWidth,
+}
+
+impl<R: Register> RegisterHandle<R> {
+    /// Probe the given register and create a new [`RegisterHandle`] for it.
+    ///
+    /// ### Possible errors
+    ///
+    /// [`SbiError::NOT_SUPPORTED`]: The register is not implemented by the
+    ///     platform.
+    ///
+    /// See [`probe_register`] for other possible errors.
+    pub fn new(register: R) -> Result<Self, SbiError> {
+        match probe_register_width(register)? {
+            Some(width) => Ok(Self {
+                register: core::marker::PhantomData,
+                width,
+            }),
+            None => Err(SbiError::NOT_SUPPORTED),
+        }
+    }
+
+    /// The register width reported when the register was probed
+    #[inline]
+    pub fn width(&self) -> CpccRegisterWidth {
+        self.width
+    }
+}
+
+impl<R: Readable> RegisterHandle<R> {
+    /// Read the full value of the register, including the upper 32 bits of a
+    /// 64-bit register when `XLEN` is 32.
+    ///
+    /// ### Possible errors
+    ///
+    /// See [`read_register`].
+    #[inline]
+    pub fn read(&self) -> Result<R::Width, SbiError> {
+        let lo = unsafe { ecall1(R::ID as usize, EXTENSION_ID, 1) }?;
+
+        #[cfg(target_arch = "riscv32")]
+        if self.width == CpccRegisterWidth::Bits64 {
+            let hi = unsafe { ecall1(R::ID as usize, EXTENSION_ID, 2) }?;
+            return Ok(<R::Width as CastRegisterValue>::from_hi_lo(hi, lo));
+        }
+
+        Ok(<R::Width as CastRegisterValue>::cast(lo))
+    }
+}
+
+impl<R: Writable> RegisterHandle<R> {
+    /// Write a value to the register.
+    ///
+    /// ### Possible errors
+    ///
+    /// See [`write_register`].
+    #[inline]
+    #[must_use = "a failed register write leaves the register unchanged"]
+    pub fn write(&self, value: R::Width) -> Result<(), SbiError> {
+        write_register_value::<R>(value)
+    }
+}
@@ -18,6 +18,9 @@ pub const EXTENSION_ID: usize = 0x4442434E;
 /// This call is non-blocking and may only perform partial or no writes to the
 /// debug console if it is unable to accept more data.
 ///
+/// If the memory region wraps around the end of the physical address space,
+/// [`SbiError::INVALID_PARAMETER`] is returned without performing the SBI call.
+///
 /// ### Safety
 ///
 /// This function is marked unsafe as it allows arbitrary reads to physical
@@ -26,18 +29,24 @@ pub const EXTENSION_ID: usize = 0x4442434E;
 /// ### Possible errors
 ///
 /// [`SbiError::INVALID_PARAMETER`]: The memory region described by the given
-///     parameters is not accessible to supervisor mode.
+///     parameters is not accessible to supervisor mode, or wraps around the
+///     end of the physical address space.
 ///
 /// [`SbiError::DENIED`]: Writing to the debug console is not allowed.
 ///
 /// [`SbiError::FAILED`]: Writing failed due to I/O errors.
 #[inline]
 #[doc(alias = "sbi_debug_console_write")]
+#[must_use = "the debug console may write fewer bytes than requested"]
 pub unsafe fn write(
     base_addr_lo: PhysicalAddress<u8>,
     base_addr_hi: PhysicalAddress<u8>,
     num_bytes: usize,
 ) -> Result<usize, SbiError> {
+    if wraps_address_space(base_addr_lo, base_addr_hi, num_bytes) {
+        return Err(SbiError::INVALID_PARAMETER);
+    }
+
     unsafe {
         ecall3(
             num_bytes,
@@ -49,6 +58,18 @@ pub unsafe fn write(
     }
 }
 
+/// Whether the `num_bytes` long memory region starting at the physical address
+/// made up of `lo` and `hi` wraps around the end of the physical address space.
+/// Carrying from the low half of the address into the high half is allowed.
+fn wraps_address_space(lo: PhysicalAddress<u8>, hi: PhysicalAddress<u8>, num_bytes: usize) -> bool {
+    let Some(last_byte_offset) = num_bytes.checked_sub(1) else {
+        return false;
+    };
+
+    let (_, carry) = (lo.0 as usize).overflowing_add(last_byte_offset);
+    carry && hi.0 as usize == usize::MAX
+}
+
 /// A convenience wrapper for `debug_console_write` which takes a single
 /// physical slice pointer instead of the manual length and address parameters.
 /// This slice ***MUST*** point into physical memory, and any pointers which are
@@ -73,6 +94,7 @@ pub unsafe fn write(
 /// [`SbiError::FAILED`]: Writing failed due to I/O errors.
 #[inline]
 #[doc(alias = "sbi_debug_console_write")]
+#[must_use = "the debug console may write fewer bytes than requested"]
 pub unsafe fn write_ptr(data: PhysicalAddress<[u8]>) -> Result<usize, SbiError> {
     unsafe {
         write(
@@ -83,6 +105,119 @@ pub unsafe fn write_ptr(data: PhysicalAddress<[u8]>) -> Result<usize, SbiError>
     }
 }
 
+/// A blocking version of [`write_ptr`] which repeatedly writes to the debug
+/// console until the entire buffer has been written or an error occurs. This
+/// slice ***MUST*** point into physical memory, and any pointers which are
+/// virtual pointers that overlap with the physical address space can cause
+/// undefined behavior.
+///
+/// This function is not appropriate to call for platforms where the amount of
+/// physical memory can exceed the pointer size, since the address of `data`
+/// only makes up the low half of the physical address. After each partial
+/// write, the low half is advanced by the number of bytes written, carrying
+/// into the high half.
+///
+/// ### Safety
+///
+/// This function is marked unsafe as it allows arbitrary reads to physical
+/// memory which can cause undefined behavior if misused.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The memory region described by the given
+///     pointer is not accessible to supervisor mode.
+///
+/// [`SbiError::DENIED`]: Writing to the debug console is not allowed.
+///
+/// [`SbiError::FAILED`]: Writing failed due to I/O errors.
+#[doc(alias = "sbi_debug_console_write")]
+#[must_use = "a failed write may have only written part of the data"]
+pub unsafe fn write_all(data: PhysicalAddress<[u8]>) -> Result<(), SbiError> {
+    write_all_with(
+        PhysicalAddress::from_ptr(data.as_ptr()),
+        PhysicalAddress::new(0),
+        data.len(),
+        // SAFETY: the caller guarantees that `data` is valid to read from
+        |lo, hi, num_bytes| unsafe { write(lo, hi, num_bytes) },
+    )
+}
+
+/// The loop behind [`write_all`], generic over the `write` call so that partial
+/// writes can be exercised by the tests. Not part of the public API.
+#[doc(hidden)]
+#[must_use = "a failed write may have only written part of the data"]
+pub fn write_all_with(
+    mut base_addr_lo: PhysicalAddress<u8>,
+    mut base_addr_hi: PhysicalAddress<u8>,
+    mut num_bytes: usize,
+    mut write: impl FnMut(PhysicalAddress<u8>, PhysicalAddress<u8>, usize) -> Result<usize, SbiError>,
+) -> Result<(), SbiError> {
+    while num_bytes > 0 {
+        let written = write(base_addr_lo, base_addr_hi, num_bytes)?.min(num_bytes);
+
+        let (lo, carry) = (base_addr_lo.0 as usize).overflowing_add(written);
+        base_addr_lo = PhysicalAddress::new(lo);
+        if carry {
+            base_addr_hi = PhysicalAddress::new((base_addr_hi.0 as usize).wrapping_add(1));
+        }
+        num_bytes -= written;
+    }
+
+    Ok(())
+}
+
+/// A witness token asserting that the virtual address of any memory accessible
+/// by the current hart is equal to its physical address, such as when running
+/// with virtual memory protection disabled (`satp.MODE` is `Bare`) or with all
+/// memory identity mapped. Holding a [`PhysicalIdentity`] allows the safe
+/// wrappers in this module, such as [`write_str`], to pass references directly
+/// to the SBI implementation as *physical* addresses.
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicalIdentity(());
+
+impl PhysicalIdentity {
+    /// Create a new [`PhysicalIdentity`] token
+    ///
+    /// ### Safety
+    ///
+    /// For as long as the returned token (or any copy of it) is in use, every
+    /// reference passed along with it must have a virtual address equal to its
+    /// physical address, and be accessible to supervisor mode at that physical
+    /// address. That is, `satp.MODE` must be `Bare`, or all memory which may be
+    /// passed alongside the token must be identity mapped. Enabling address
+    /// translation which breaks this requirement while the token is still in
+    /// use can cause the SBI implementation to read or write arbitrary physical
+    /// memory.
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        Self(())
+    }
+}
+
+/// Write the entire string to the debug console, retrying any partial writes
+/// until the whole string has been written or an error occurs. See
+/// [`write_all`] for more information.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The memory region of the string is not
+///     accessible to supervisor mode.
+///
+/// [`SbiError::DENIED`]: Writing to the debug console is not allowed.
+///
+/// [`SbiError::FAILED`]: Writing failed due to I/O errors.
+#[doc(alias = "sbi_debug_console_write")]
+#[must_use = "a failed write may have only written part of the data"]
+pub fn write_str(_identity: &PhysicalIdentity, s: &str) -> Result<(), SbiError> {
+    // SAFETY: the `PhysicalIdentity` token guarantees that the virtual address
+    // of the string is also its physical address
+    unsafe {
+        write_all(PhysicalAddress::from_ptr(
+            s.as_bytes() as *const [u8] as *mut [u8]
+        ))
+    }
+}
+
 /// Perform a read from the debug console of size `num_bytes` to the given
 /// *physical* address specified by `physical_base_addr_lo` and
 /// `physical_base_addr_hi`. The return value is the number of bytes read from
@@ -91,6 +226,9 @@ pub unsafe fn write_ptr(data: PhysicalAddress<[u8]>) -> Result<usize, SbiError>
 /// This call is non-blocking and will not perform any writes to memory if there
 /// is no data waiting to be read on the debug console.
 ///
+/// If the memory region wraps around the end of the physical address space,
+/// [`SbiError::INVALID_PARAMETER`] is returned without performing the SBI call.
+///
 /// ### Safety
 ///
 /// This function is marked unsafe as it allows arbitrary writes to physical
@@ -99,18 +237,24 @@ pub unsafe fn write_ptr(data: PhysicalAddress<[u8]>) -> Result<usize, SbiError>
 /// ### Possible errors
 ///
 /// [`SbiError::INVALID_PARAMETER`]: The memory region described by the given
-///     parameters is not accessible to supervisor mode.
+///     parameters is not accessible to supervisor mode, or wraps around the
+///     end of the physical address space.
 ///
 /// [`SbiError::DENIED`]: Reads from the debug console is not allowed.
 ///
 /// [`SbiError::FAILED`]: Reading failed due to I/O errors.
 #[inline]
 #[doc(alias = "sbi_debug_console_read")]
+#[must_use = "the number of bytes read is needed to know how much of the buffer was filled"]
 pub unsafe fn read(
     physical_base_addr_lo: PhysicalAddress<u8>,
     physical_base_addr_hi: PhysicalAddress<u8>,
     num_bytes: usize,
 ) -> Result<usize, SbiError> {
+    if wraps_address_space(physical_base_addr_lo, physical_base_addr_hi, num_bytes) {
+        return Err(SbiError::INVALID_PARAMETER);
+    }
+
     unsafe {
         ecall3(
             num_bytes,
@@ -146,6 +290,7 @@ pub unsafe fn read(
 /// [`SbiError::FAILED`]: Writing failed due to I/O errors.
 #[inline]
 #[doc(alias = "sbi_debug_console_read")]
+#[must_use = "the number of bytes read is needed to know how much of the buffer was filled"]
 pub unsafe fn read_ptr(buffer: PhysicalAddress<[u8]>) -> Result<usize, SbiError> {
     unsafe {
         read(
@@ -156,9 +301,69 @@ pub unsafe fn read_ptr(buffer: PhysicalAddress<[u8]>) -> Result<usize, SbiError>
     }
 }
 
+/// Read from the debug console into the given buffer, returning the sub-slice
+/// of the buffer which was filled. This call is non-blocking, and the returned
+/// slice will be empty if there is no data waiting to be read on the debug
+/// console. See [`read_ptr`] for more information.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The memory region of the buffer is not
+///     accessible to supervisor mode.
+///
+/// [`SbiError::DENIED`]: Reads from the debug console is not allowed.
+///
+/// [`SbiError::FAILED`]: Reading failed due to I/O errors.
+#[inline]
+#[doc(alias = "sbi_debug_console_read")]
+#[must_use = "the returned slice is the part of the buffer which was filled"]
+pub fn read_into<'a>(
+    _identity: &PhysicalIdentity,
+    buffer: &'a mut [u8],
+) -> Result<&'a mut [u8], SbiError> {
+    // SAFETY: the `PhysicalIdentity` token guarantees that the virtual address
+    // of the buffer is also its physical address
+    let read = unsafe { read_ptr(PhysicalAddress::from_ptr(buffer as *mut [u8])) }?;
+
+    let len = read.min(buffer.len());
+
+    Ok(&mut buffer[..len])
+}
+
+/// Read from the debug console into the given buffer, then write back exactly
+/// the bytes which were read, returning the number of bytes echoed. Reading is
+/// non-blocking, so `0` is returned if there is no data waiting to be read.
+/// Writing is blocking, as with [`write_all`]. This is useful during bring-up
+/// to verify that both directions of the debug console are working. See
+/// [`read_ptr`] for more information.
+///
+/// ### Safety
+///
+/// This function is marked unsafe as it allows arbitrary reads and writes to
+/// physical memory which can cause undefined behavior if misused.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The memory region described by the given
+///     pointer is not accessible to supervisor mode.
+///
+/// [`SbiError::DENIED`]: Reading from or writing to the debug console is not
+///     allowed.
+///
+/// [`SbiError::FAILED`]: Reading or writing failed due to I/O errors.
+#[must_use = "a failed loopback may have read input without echoing it"]
+pub unsafe fn loopback_once(buffer: PhysicalAddress<[u8]>) -> Result<usize, SbiError> {
+    let read = unsafe { read_ptr(buffer) }?.min(buffer.len());
+    let echo = core::ptr::slice_from_raw_parts_mut(buffer.as_ptr(), read);
+    unsafe { write_all(PhysicalAddress::from_ptr(echo)) }?;
+
+    Ok(read)
+}
+
 /// Write a single byte to the debug console. This call is blocking and will
 /// only return after either successfully writing the byte to the debug console
-/// or an I/O error occurs.
+/// or an I/O error occurs. The SBI implementation returns no value on success,
+/// so a successful return means the byte was written.
 ///
 /// ### Possible errors
 ///
@@ -167,6 +372,309 @@ pub unsafe fn read_ptr(buffer: PhysicalAddress<[u8]>) -> Result<usize, SbiError>
 /// [`SbiError::FAILED`]: Writing failed due to I/O errors.
 #[inline]
 #[doc(alias = "sbi_debug_console_write_byte")]
-pub fn write_byte(byte: u8) -> Result<usize, SbiError> {
-    unsafe { ecall1(usize::from(byte), EXTENSION_ID, 2) }
+#[must_use = "a failed write means the byte was not written"]
+pub fn write_byte(byte: u8) -> Result<(), SbiError> {
+    unsafe { ecall1(usize::from(byte), EXTENSION_ID, 2) }.map(drop)
+}
+
+/// Write all of `bytes` to the debug console with as few SBI calls as
+/// possible. The buffer is handed to the SBI implementation with the block
+/// `write` call, and only if the SBI implementation rejects the buffer as not
+/// being accessible (e.g. because `bytes` is not identity mapped) are the
+/// remaining bytes written one at a time with [`write_byte`].
+///
+/// ### Safety
+///
+/// The virtual address of `bytes` must be equal to its physical address, or
+/// refer to memory which is inaccessible to supervisor mode.
+unsafe fn write_batched(bytes: &[u8]) -> Result<(), SbiError> {
+    let mut remaining = bytes;
+
+    while !remaining.is_empty() {
+        let written = unsafe {
+            write(
+                PhysicalAddress::from_ptr(remaining.as_ptr().cast_mut()),
+                PhysicalAddress::new(0),
+                remaining.len(),
+            )
+        };
+
+        match written {
+            Ok(written) => remaining = &remaining[written.min(remaining.len())..],
+            // The buffer couldn't be accessed as physical memory, so fall back
+            // to passing the bytes directly in registers
+            Err(SbiError::INVALID_PARAMETER) => {
+                return remaining.iter().try_for_each(|&byte| write_byte(byte));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// A [`core::fmt::Write`] implementation which writes to the debug console.
+///
+/// Strings are copied into a small bounce buffer on the stack and written with
+/// the block `write` call, retrying any partial writes until the entire string
+/// has been written or an error occurs.
+///
+/// ### Performance
+///
+/// Each 64 byte chunk of a string costs a single SBI call when the SBI
+/// implementation consumes the whole chunk at once, so formatting a 100 byte
+/// string typically takes two calls rather than the one hundred that
+/// [`write_byte`] would. Partial writes cost an additional call each. If the
+/// SBI implementation rejects the bounce buffer with
+/// [`SbiError::INVALID_PARAMETER`], the rest of the chunk falls back to one
+/// [`write_byte`] call per byte.
+///
+/// Since the bounce buffer is passed to the SBI implementation as a *physical*
+/// address, the stack of the hart writing to a [`Writer`] must be identity
+/// mapped (or virtual memory protection must be disabled) for the duration of
+/// the write.
+#[derive(Debug)]
+pub struct Writer(());
+
+impl Writer {
+    /// Create a new [`Writer`]
+    ///
+    /// ### Safety
+    ///
+    /// The stack of any hart that writes using the returned [`Writer`] must be
+    /// identity mapped, such that the virtual address of the bounce buffer is
+    /// equal to its physical address. Otherwise the SBI implementation will
+    /// read arbitrary physical memory.
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        Self(())
+    }
+}
+
+impl core::fmt::Write for Writer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut buffer = [0u8; 64];
+
+        for chunk in s.as_bytes().chunks(buffer.len()) {
+            buffer[..chunk.len()].copy_from_slice(chunk);
+
+            unsafe { write_batched(&buffer[..chunk.len()]) }.map_err(|_| core::fmt::Error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A handle to the debug console which implements [`ConsoleExt`], as well as
+/// the [`embedded_io::Read`] and [`embedded_io::Write`] traits when the
+/// `embedded-io` feature is enabled.
+///
+/// Buffers passed to the trait methods are passed directly to the SBI
+/// implementation as *physical* addresses, so all buffers used with a
+/// [`DebugConsole`] must be identity mapped (or virtual memory protection must
+/// be disabled).
+#[derive(Debug)]
+pub struct DebugConsole(());
+
+impl DebugConsole {
+    /// Create a new [`DebugConsole`] handle
+    ///
+    /// ### Safety
+    ///
+    /// Any buffer read into or written from using the returned
+    /// [`DebugConsole`] must be identity mapped, such that its virtual address
+    /// is equal to its physical address. Otherwise the SBI implementation will
+    /// read or write arbitrary physical memory.
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        Self(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Error for SbiError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match *self {
+            SbiError::INVALID_PARAMETER | SbiError::INVALID_ADDRESS => {
+                embedded_io::ErrorKind::InvalidInput
+            }
+            SbiError::DENIED => embedded_io::ErrorKind::PermissionDenied,
+            SbiError::NOT_SUPPORTED => embedded_io::ErrorKind::Unsupported,
+            _ => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::ErrorType for DebugConsole {
+    type Error = SbiError;
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Read for DebugConsole {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // `embedded_io::Read::read` is required to block until at least one
+        // byte has been read, but the SBI call is non-blocking
+        loop {
+            match unsafe { read_ptr(PhysicalAddress::from_ptr(buf as *mut [u8])) }? {
+                0 => continue,
+                n => break Ok(n),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Write for DebugConsole {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // `embedded_io::Write::write` is required to block until at least one
+        // byte has been written, but the SBI call is non-blocking
+        loop {
+            match unsafe { write_ptr(PhysicalAddress::from_ptr(buf as *const [u8] as *mut [u8])) }?
+            {
+                0 => continue,
+                n => break Ok(n),
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Write `s` to whichever console is available, for use in panic handlers and
+/// other paths that can't propagate errors. Each byte is written with
+/// [`write_byte`], which passes the byte in a register and so works regardless
+/// of how memory is mapped. If the debug console extension is not supported,
+/// the remaining bytes are written with [`crate::legacy::console_putchar`]
+/// instead.
+///
+/// This function is best-effort: it blocks until every byte has been handed to
+/// the SBI implementation, but any byte the debug console fails to write for
+/// another reason (e.g. [`SbiError::DENIED`] or [`SbiError::FAILED`]) is
+/// silently dropped, and the legacy console silently discards output when no
+/// console exists.
+pub fn emergency_print(s: &str) {
+    let mut bytes = s.bytes();
+
+    for byte in bytes.by_ref() {
+        match write_byte(byte) {
+            Ok(()) => {}
+            Err(SbiError::NOT_SUPPORTED) => {
+                crate::legacy::console_putchar(byte);
+                break;
+            }
+            Err(_) => {}
+        }
+    }
+
+    bytes.for_each(crate::legacy::console_putchar);
+}
+
+/// A console which bytes can be written to and read from, implemented by both
+/// the legacy console ([`crate::legacy::LegacyConsole`]) and the debug console
+/// ([`DebugConsole`]) so that code can be generic over whichever is available.
+/// See [`best_available`] to select a console at runtime.
+pub trait ConsoleExt {
+    /// Write a single byte to the console
+    fn put_byte(&mut self, byte: u8) -> Result<(), SbiError>;
+
+    /// Read a single byte from the console, returning [`None`] if there is no
+    /// data waiting to be read
+    fn get_byte(&mut self) -> Result<Option<u8>, SbiError>;
+
+    /// Write all of the given bytes to the console. The default implementation
+    /// writes each byte with [`ConsoleExt::put_byte`], costing one SBI call per
+    /// byte, while [`DebugConsole`] batches the bytes into as few block writes
+    /// as possible.
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SbiError> {
+        bytes.iter().try_for_each(|&byte| self.put_byte(byte))
+    }
+}
+
+impl ConsoleExt for DebugConsole {
+    fn put_byte(&mut self, byte: u8) -> Result<(), SbiError> {
+        write_byte(byte)
+    }
+
+    fn get_byte(&mut self) -> Result<Option<u8>, SbiError> {
+        let mut byte = 0u8;
+        let read = unsafe {
+            read_ptr(PhysicalAddress::from_ptr(
+                core::slice::from_mut(&mut byte) as *mut [u8]
+            ))
+        }?;
+
+        Ok((read > 0).then_some(byte))
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SbiError> {
+        unsafe { write_batched(bytes) }
+    }
+}
+
+impl ConsoleExt for crate::legacy::LegacyConsole {
+    fn put_byte(&mut self, byte: u8) -> Result<(), SbiError> {
+        crate::legacy::console_putchar(byte);
+        Ok(())
+    }
+
+    fn get_byte(&mut self) -> Result<Option<u8>, SbiError> {
+        Ok(crate::legacy::console_getchar())
+    }
+}
+
+/// The console selected by [`best_available`]
+#[derive(Debug)]
+pub enum AvailableConsole {
+    /// The debug console extension is available
+    DebugConsole(DebugConsole),
+    /// The debug console extension is unavailable, so the legacy console is
+    /// used instead
+    Legacy(crate::legacy::LegacyConsole),
+}
+
+impl ConsoleExt for AvailableConsole {
+    fn put_byte(&mut self, byte: u8) -> Result<(), SbiError> {
+        match self {
+            Self::DebugConsole(console) => console.put_byte(byte),
+            Self::Legacy(console) => console.put_byte(byte),
+        }
+    }
+
+    fn get_byte(&mut self) -> Result<Option<u8>, SbiError> {
+        match self {
+            Self::DebugConsole(console) => console.get_byte(),
+            Self::Legacy(console) => console.get_byte(),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SbiError> {
+        match self {
+            Self::DebugConsole(console) => console.write_bytes(bytes),
+            Self::Legacy(console) => console.write_bytes(bytes),
+        }
+    }
+}
+
+/// Select the best available console by probing for the debug console
+/// extension, falling back to the legacy console if it is unavailable.
+///
+/// ### Safety
+///
+/// See [`DebugConsole::new`].
+pub unsafe fn best_available() -> AvailableConsole {
+    match crate::base::probe_extension(EXTENSION_ID).is_available() {
+        true => AvailableConsole::DebugConsole(unsafe { DebugConsole::new() }),
+        false => AvailableConsole::Legacy(crate::legacy::LegacyConsole),
+    }
 }
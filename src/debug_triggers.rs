@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: MPL-2.0
+// SPDX-FileCopyrightText: 2024 repnop
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License,
+// v. 2.0. If a copy of the MPL was not distributed with this file, You can
+// obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{ecall1, ecall2, ecall3, PhysicalAddress, SbiError};
+
+/// Debug Triggers extension ID
+pub const EXTENSION_ID: usize = 0x44425452;
+
+/// A single entry in the debug triggers shared memory region. The shared
+/// memory region is an array of these entries, and the number of entries used
+/// is determined by the `trig_count` parameter of the relevant calls.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct Trigger {
+    /// When reading triggers, this is the trigger state written by the SBI
+    /// implementation. When installing triggers, this is set to the index of
+    /// the installed trigger by the SBI implementation, and when updating
+    /// triggers, this is the index of the trigger to update.
+    pub tstate: usize,
+    /// The `tdata1` trigger CSR value
+    pub tdata1: usize,
+    /// The `tdata2` trigger CSR value
+    pub tdata2: usize,
+    /// The `tdata3` trigger CSR value
+    pub tdata3: usize,
+}
+
+const _: () = assert!(core::mem::size_of::<Trigger>() == 4 * core::mem::size_of::<usize>());
+
+/// Flags for setting the debug triggers shared memory region
+///
+/// There are currently no valid flags for this parameter, so always construct it with [`SharedMemoryFlags::NONE`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SharedMemoryFlags(usize);
+
+impl SharedMemoryFlags {
+    /// No flags
+    pub const NONE: Self = Self(0);
+}
+
+/// A bitmask of trigger indices to be acted upon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerIndexMask {
+    base: usize,
+    mask: usize,
+}
+
+impl TriggerIndexMask {
+    /// Create a new [`TriggerIndexMask`] with the given base and no trigger
+    /// indices selected
+    #[inline]
+    pub const fn new(base: usize) -> Self {
+        Self { base, mask: 0 }
+    }
+
+    /// Create a new [`TriggerIndexMask`] from the given trigger index, making
+    /// it the base and selecting it
+    #[inline]
+    pub const fn from(trigger_idx: usize) -> Self {
+        Self {
+            base: trigger_idx,
+            mask: 1,
+        }
+    }
+
+    /// Select the given trigger index. If `trigger_idx` is out of the range of
+    /// available selectable trigger indices, the [`TriggerIndexMask`] is
+    /// unchanged.
+    #[inline]
+    #[must_use]
+    pub const fn with(mut self, trigger_idx: usize) -> Self {
+        if trigger_idx >= self.base && trigger_idx < (self.base + usize::BITS as usize) {
+            self.mask |= 1 << (trigger_idx - self.base);
+        }
+
+        self
+    }
+}
+
+/// Retrieve the number of debug triggers available to the calling hart which
+/// can support the trigger configuration described by `trig_tdata1`. If
+/// `trig_tdata1` is `0`, the total number of debug triggers available to the
+/// calling hart is returned.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The debug triggers extension is not
+///     implemented by the SBI implementation.
+#[inline]
+#[doc(alias = "sbi_debug_num_triggers")]
+pub fn num_triggers(trig_tdata1: usize) -> Result<usize, SbiError> {
+    unsafe { ecall1(trig_tdata1, EXTENSION_ID, 0) }
+}
+
+/// Set the shared memory region used to pass [`Trigger`] entries between
+/// supervisor software and the SBI implementation for the calling hart. Passing
+/// all-ones (`usize::MAX`) for both `lo` and `hi` disables the shared memory
+/// region. Until the shared memory region has been set, calls which rely on it,
+/// such as [`read_triggers`], fail with
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`].
+///
+/// ### Safety
+///
+/// This function allows having the SBI write to arbitrary physical memory, and
+/// thus can cause undefined behavior if used incorrectly.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The `flags` parameter is not zero.
+///
+/// [`SbiError::INVALID_ADDRESS`]: The shared memory region described by the
+///     given parameters is not writable or does not satisfy other requirements
+///     of the SBI implementation.
+///
+/// [`SbiError::FAILED`]: The request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_debug_set_shmem")]
+pub unsafe fn set_shared_memory(
+    lo: PhysicalAddress<Trigger>,
+    hi: PhysicalAddress<Trigger>,
+    flags: SharedMemoryFlags,
+) -> Result<(), SbiError> {
+    unsafe { ecall3(lo.0 as usize, hi.0 as usize, flags.0, EXTENSION_ID, 1) }.map(drop)
+}
+
+/// Disable the debug triggers shared memory region for the calling hart by
+/// setting its physical address to all-ones (`usize::MAX`). After disabling,
+/// the SBI implementation will no longer access the previously set shared
+/// memory region.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The `flags` parameter is not zero.
+#[inline]
+pub fn disable_shared_memory(flags: SharedMemoryFlags) -> Result<(), SbiError> {
+    unsafe { ecall3(usize::MAX, usize::MAX, flags.0, EXTENSION_ID, 1) }.map(drop)
+}
+
+/// Read the state of `trig_count` debug triggers starting at the trigger index
+/// `trig_idx_base` into the shared memory region.
+///
+/// ### Safety
+///
+/// The shared memory region must have been set with [`set_shared_memory`], be
+/// large enough to hold `trig_count` [`Trigger`] entries, and must not be
+/// accessed by the calling hart for the duration of the call.
+///
+/// ### Possible errors
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory region is not
+///     enabled.
+///
+/// [`SbiError::INVALID_PARAMETER`]: `trig_idx_base` or `trig_count` is
+///     invalid.
+///
+/// [`SbiError::FAILED`]: The request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_debug_read_triggers")]
+pub unsafe fn read_triggers(trig_idx_base: usize, trig_count: usize) -> Result<(), SbiError> {
+    unsafe { ecall2(trig_idx_base, trig_count, EXTENSION_ID, 2) }.map(drop)
+}
+
+/// Install `trig_count` debug triggers described by the [`Trigger`] entries in
+/// the shared memory region. On success, the index of each installed trigger is
+/// written to the [`Trigger::tstate`] field of its entry.
+///
+/// ### Safety
+///
+/// The shared memory region must have been set with [`set_shared_memory`], be
+/// large enough to hold `trig_count` [`Trigger`] entries, and must not be
+/// accessed by the calling hart for the duration of the call.
+///
+/// ### Possible errors
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory region is not
+///     enabled.
+///
+/// [`SbiError::INVALID_PARAMETER`]: One or more of the trigger configurations
+///     is invalid.
+///
+/// [`SbiError::FAILED`]: The request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_debug_install_triggers")]
+pub unsafe fn install_triggers(trig_count: usize) -> Result<(), SbiError> {
+    unsafe { ecall1(trig_count, EXTENSION_ID, 3) }.map(drop)
+}
+
+/// Update `trig_count` previously installed debug triggers described by the
+/// [`Trigger`] entries in the shared memory region, where the
+/// [`Trigger::tstate`] field of each entry holds the index of the trigger to
+/// update.
+///
+/// ### Safety
+///
+/// The shared memory region must have been set with [`set_shared_memory`], be
+/// large enough to hold `trig_count` [`Trigger`] entries, and must not be
+/// accessed by the calling hart for the duration of the call.
+///
+/// ### Possible errors
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory region is not
+///     enabled.
+///
+/// [`SbiError::INVALID_PARAMETER`]: One or more of the trigger indices or
+///     configurations is invalid.
+///
+/// [`SbiError::FAILED`]: The request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_debug_update_triggers")]
+pub unsafe fn update_triggers(trig_count: usize) -> Result<(), SbiError> {
+    unsafe { ecall1(trig_count, EXTENSION_ID, 4) }.map(drop)
+}
+
+/// Uninstall the debug triggers described by the given [`TriggerIndexMask`].
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: One or more of the trigger indices is
+///     invalid.
+#[inline]
+#[doc(alias = "sbi_debug_uninstall_triggers")]
+pub fn uninstall_triggers(trigger_mask: TriggerIndexMask) -> Result<(), SbiError> {
+    unsafe { ecall2(trigger_mask.base, trigger_mask.mask, EXTENSION_ID, 5) }.map(drop)
+}
+
+/// Enable the debug triggers described by the given [`TriggerIndexMask`].
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: One or more of the trigger indices is
+///     invalid.
+#[inline]
+#[doc(alias = "sbi_debug_enable_triggers")]
+pub fn enable_triggers(trigger_mask: TriggerIndexMask) -> Result<(), SbiError> {
+    unsafe { ecall2(trigger_mask.base, trigger_mask.mask, EXTENSION_ID, 6) }.map(drop)
+}
+
+/// Disable the debug triggers described by the given [`TriggerIndexMask`].
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: One or more of the trigger indices is
+///     invalid.
+#[inline]
+#[doc(alias = "sbi_debug_disable_triggers")]
+pub fn disable_triggers(trigger_mask: TriggerIndexMask) -> Result<(), SbiError> {
+    unsafe { ecall2(trigger_mask.base, trigger_mask.mask, EXTENSION_ID, 7) }.map(drop)
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+// SPDX-FileCopyrightText: 2022 repnop
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License,
+// v. 2.0. If a copy of the MPL was not distributed with this file, You can
+// obtain one at https://mozilla.org/MPL/2.0/.
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A standard SBI extension, implemented by the zero-sized marker types in
+/// this module so that an extension can be named at the type level, such as
+/// when probing it with [`crate::base::probe`]
+pub trait Extension: sealed::Sealed {
+    /// The extension ID
+    const ID: usize;
+}
+
+/// Steal-time Accounting extension ID. This crate does not implement the
+/// extension's calls, but the ID is needed to probe for it.
+pub const STEAL_TIME_ACCOUNTING_EXTENSION_ID: usize = 0x535441;
+
+macro_rules! extensions {
+    ($($(#[$meta:meta])* $name:ident => $id:expr,)+) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl sealed::Sealed for $name {}
+            impl Extension for $name {
+                const ID: usize = $id;
+            }
+        )+
+    };
+}
+
+extensions! {
+    /// Base extension
+    Base => crate::base::EXTENSION_ID,
+    /// Timer extension
+    Timer => crate::timer::EXTENSION_ID,
+    /// IPI extension
+    Ipi => crate::ipi::EXTENSION_ID,
+    /// RFENCE extension
+    Rfence => crate::rfence::EXTENSION_ID,
+    /// Hart State Management extension
+    HartStateManagement => crate::hart_state_management::EXTENSION_ID,
+    /// System Reset extension
+    SystemReset => crate::system_reset::EXTENSION_ID,
+    /// Performance Monitoring Unit extension
+    PerformanceMonitoringUnit => crate::performance_monitoring_unit::EXTENSION_ID,
+    /// Debug Console extension
+    DebugConsole => crate::debug_console::EXTENSION_ID,
+    /// System Suspend extension
+    SystemSuspend => crate::system_suspend::EXTENSION_ID,
+    /// Collaborative Processor Performance Control extension
+    CollaborativeProcessorPerformanceControl =>
+        crate::collaborative_processor_performance_control::EXTENSION_ID,
+    /// Nested Acceleration extension
+    NestedAcceleration => crate::nested_acceleration::EXTENSION_ID,
+    /// Steal-time Accounting extension
+    StealTimeAccounting => STEAL_TIME_ACCOUNTING_EXTENSION_ID,
+    /// Firmware Features extension
+    FirmwareFeatures => crate::firmware_features::EXTENSION_ID,
+    /// Debug Triggers extension
+    DebugTriggers => crate::debug_triggers::EXTENSION_ID,
+}
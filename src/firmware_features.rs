@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MPL-2.0
+// SPDX-FileCopyrightText: 2024 repnop
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License,
+// v. 2.0. If a copy of the MPL was not distributed with this file, You can
+// obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::{ecall1, ecall3, RestrictedRange, SbiError};
+
+/// Firmware Features extension ID
+pub const EXTENSION_ID: usize = 0x46574654;
+
+/// A firmware feature which can be configured by supervisor software
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Feature {
+    /// Control misaligned access exception delegation to supervisor mode
+    #[doc(alias = "MISALIGNED_EXC_DELEG")]
+    MisalignedExceptionDelegation,
+    /// Control landing pad support for supervisor mode
+    #[doc(alias = "LANDING_PAD")]
+    LandingPad,
+    /// Control shadow stack support for supervisor mode
+    #[doc(alias = "SHADOW_STACK")]
+    ShadowStack,
+    /// Control double trap support
+    #[doc(alias = "DOUBLE_TRAP")]
+    DoubleTrap,
+    /// Control hardware updating of the PTE A/D bits for supervisor mode
+    #[doc(alias = "PTE_AD_HW_UPDATING")]
+    PteAdHardwareUpdating,
+    /// Control the pointer masking tag length for supervisor mode
+    #[doc(alias = "POINTER_MASKING_PMLEN")]
+    PointerMaskingPmlen,
+    /// Platform specific local (per-hart) feature
+    PlatformSpecificLocal(RestrictedRange<0x40000000, 0x7FFFFFFF>),
+    /// Platform specific global (system-wide) feature
+    PlatformSpecificGlobal(RestrictedRange<0xC0000000, 0xFFFFFFFF>),
+}
+
+impl From<Feature> for u32 {
+    fn from(value: Feature) -> Self {
+        match value {
+            Feature::MisalignedExceptionDelegation => 0,
+            Feature::LandingPad => 1,
+            Feature::ShadowStack => 2,
+            Feature::DoubleTrap => 3,
+            Feature::PteAdHardwareUpdating => 4,
+            Feature::PointerMaskingPmlen => 5,
+            Feature::PlatformSpecificLocal(n) => n.0,
+            Feature::PlatformSpecificGlobal(n) => n.0,
+        }
+    }
+}
+
+/// Flags used when setting the value of a firmware feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlags(usize);
+
+impl FeatureFlags {
+    /// No flags
+    pub const NONE: Self = Self(0);
+    /// Lock the feature value, preventing any further changes until the next
+    /// hart reset (or system reset, for global features)
+    pub const LOCK: Self = Self(1 << 0);
+}
+
+impl core::ops::BitOr for FeatureFlags {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for FeatureFlags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Default for FeatureFlags {
+    #[inline]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Set the configuration value of the given firmware feature. Local features
+/// are only configured for the calling hart, while global features are
+/// configured for all harts.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The feature is not reserved, but is not
+///     implemented or not available to supervisor mode.
+///
+/// [`SbiError::INVALID_PARAMETER`]: The provided value or flags are invalid,
+///     or the feature is reserved.
+///
+/// [`SbiError::DENIED`]: The feature was previously locked.
+///
+/// [`SbiError::FAILED`]: The set request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_fwft_set")]
+pub fn set(feature: Feature, value: usize, flags: FeatureFlags) -> Result<(), SbiError> {
+    unsafe { ecall3(u32::from(feature) as usize, value, flags.0, EXTENSION_ID, 0) }.map(drop)
+}
+
+/// Retrieve the current configuration value of the given firmware feature.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The feature is not reserved, but is not
+///     implemented or not available to supervisor mode.
+///
+/// [`SbiError::INVALID_PARAMETER`]: The feature is reserved.
+///
+/// [`SbiError::FAILED`]: The get request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "sbi_fwft_get")]
+pub fn get(feature: Feature) -> Result<usize, SbiError> {
+    unsafe { ecall1(u32::from(feature) as usize, EXTENSION_ID, 1) }
+}
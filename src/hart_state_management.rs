@@ -5,6 +5,16 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
+//! The SBI specification provides no call to retrieve the hart ID of the
+//! calling hart, so this module does not either. The hart ID is instead
+//! provided by the boot protocol: the SBI implementation passes it in `a0` when
+//! entering supervisor mode at boot, as well as to the entry point given to
+//! [`hart_start`] and the resume address of a non-retentive suspend.
+//! Supervisor software should save it from there, such as in a per-hart data
+//! structure pointed to by `tp` or `sscratch`. The calling hart does not need
+//! its own hart ID to query its state, since a hart which is executing is
+//! always [`HartState::Started`].
+
 use crate::{ecall0, ecall1, ecall3, PhysicalAddress, RestrictedRange, SbiError};
 
 /// Hart state management extension ID
@@ -49,16 +59,43 @@ pub unsafe fn hart_start(
     unsafe { ecall3(hart_id, start_addr.0 as usize, private, EXTENSION_ID, 0).map(drop) }
 }
 
+/// Start the specific hart ID executing the given `entry` function, which is
+/// passed the hart ID as its first argument (`a0`) and the `opaque` value as
+/// its second argument (`a1`). See [`hart_start`] for the state of the hart
+/// when it begins execution.
+///
+/// ### Safety
+///
+/// The address of `entry` must be its physical address, since it will be
+/// executed with no memory protection, and it must be properly set up to
+/// handle execution with an undefined register state (except `a0` and `a1`),
+/// such as having no valid stack pointer.
+///
+/// ### Possible errors
+///
+/// See [`hart_start`].
+#[inline]
+pub unsafe fn hart_start_fn(
+    hart_id: usize,
+    entry: extern "C" fn(usize, usize) -> !,
+    opaque: usize,
+) -> Result<(), SbiError> {
+    unsafe { hart_start(hart_id, PhysicalAddress::from_ptr(entry as *mut ()), opaque) }
+}
+
 /// This SBI call stops S-mode execution on the current hart and yields
 /// execution back to the SBI implementation. Note: **this function must be
 /// called with supervisor and user interrupts disabled.**
 ///
 /// ### Possible errors
 ///
-/// [`SbiError::FAILED`]: The request failed for an unknown reason.
+/// [`SbiError::FAILED`]: The request failed for an unknown reason, or the SBI
+///     implementation incorrectly returned success without stopping the hart.
 pub fn hart_stop() -> Result<core::convert::Infallible, SbiError> {
     match unsafe { ecall0(EXTENSION_ID, 1) } {
-        Ok(_) => unreachable!("SBI returned `Ok` when stopping the current hart"),
+        // A successful stop never returns, so treat a spurious success as a
+        // failure to stop rather than panicking
+        Ok(_) => Err(SbiError::FAILED),
         Err(e) => Err(e),
     }
 }
@@ -72,6 +109,43 @@ pub fn hart_state(hart_id: usize) -> Result<HartState, SbiError> {
     unsafe { ecall1(hart_id, EXTENSION_ID, 2).map(HartState::from_usize) }
 }
 
+/// Deprecated alias of [`hart_state`]
+#[inline]
+#[deprecated = "use `hart_state` instead"]
+pub fn hart_status(hart_id: usize) -> Result<HartState, SbiError> {
+    hart_state(hart_id)
+}
+
+/// Busy-wait until the specified hart ID reaches the given [`HartState`] by
+/// repeatedly polling [`hart_state`]. This function does not time out, so
+/// callers that need a timeout or backoff should poll [`hart_state`]
+/// themselves.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The specified hart ID is not valid.
+pub fn wait_for_state(hart_id: usize, state: HartState) -> Result<(), SbiError> {
+    while hart_state(hart_id)? != state {
+        core::hint::spin_loop();
+    }
+
+    Ok(())
+}
+
+/// Busy-wait until the specified hart ID reaches the [`HartState::Started`]
+/// state. See [`wait_for_state`] for more information.
+#[inline]
+pub fn wait_for_started(hart_id: usize) -> Result<(), SbiError> {
+    wait_for_state(hart_id, HartState::Started)
+}
+
+/// Busy-wait until the specified hart ID reaches the [`HartState::Stopped`]
+/// state. See [`wait_for_state`] for more information.
+#[inline]
+pub fn wait_for_stopped(hart_id: usize) -> Result<(), SbiError> {
+    wait_for_state(hart_id, HartState::Stopped)
+}
+
 /// Places the current hart into a suspended or low power state specified by the
 /// `suspend_type` parameter. The hart will resume normal execution after an
 /// interrupt or platform-specific hardware event. The resume behavior depends
@@ -108,13 +182,163 @@ pub fn hart_state(hart_id: usize) -> Result<HartState, SbiError> {
 ///     implemented.
 ///
 /// [`SbiError::FAILED`]: The suspension request failed for an unknown reason.
+#[deprecated = "use `suspend_retentive` or `suspend_non_retentive` instead"]
 pub unsafe fn hart_suspend(suspend_type: SuspendType) -> Result<(), SbiError> {
+    unsafe { suspend(suspend_type) }
+}
+
+unsafe fn suspend(suspend_type: SuspendType) -> Result<(), SbiError> {
     let (value, resume_addr, opaque) = suspend_type.to_values();
     unsafe { ecall3(value as usize, resume_addr, opaque, EXTENSION_ID, 3).map(drop) }
 }
 
-/// The type of suspension to be executed whe ncalling [`hart_suspend`]
+/// Places the current hart into the given retentive suspend state. The hart
+/// resumes after an interrupt or platform-specific hardware event, at which
+/// point this function returns normally with all supervisor register and CSR
+/// state preserved. Interrupts which are enabled in the `sie` CSR will wake
+/// the hart even if `sstatus.SIE` is `0`. Unlike [`suspend_non_retentive`],
+/// this function is safe since retentive suspension never resumes execution at
+/// an arbitrary address.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The given platform-specific suspend type is
+///     not valid.
+///
+/// [`SbiError::NOT_SUPPORTED`]: The given suspend type is valid but not
+///     implemented.
+///
+/// [`SbiError::FAILED`]: The suspension request failed for an unknown reason.
+#[inline]
+pub fn suspend_retentive(kind: RetentiveSuspendType) -> Result<(), SbiError> {
+    unsafe { suspend(kind.into()) }
+}
+
+/// Places the current hart into the given non-retentive suspend state. No
+/// supervisor register or CSR state is saved, and when the hart is woken it
+/// resumes execution at the `resume_address` of `kind` with:
+///
+/// `satp` is reset to a value of `0` (virtual memory protection disabled)
+///
+/// `sstatus.SIE` is reset to a value of `0` (supervisor interrupts disabled)
+///
+/// `a0` contains the current hart ID
+///
+/// `a1` contains the value of the `opaque` parameter
+///
+/// All other register states are undefined
+///
+/// Since a successful suspension never returns, this function only returns
+/// when the suspension request fails.
+///
+/// ### Safety
+///
+/// This function is unsafe as it allows arbitrary execution at a given physical
+/// address, which may cause undefined behavior if used incorrectly.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The given platform-specific suspend type is
+///     not valid.
+///
+/// [`SbiError::INVALID_ADDRESS`]: An invalid address was given for
+///     `resume_address` because it was either: an invalid physical address, or
+///     the resume address is probited by Physical Memory Protection (PMP) to
+///     run in supervisor mode.
+///
+/// [`SbiError::NOT_SUPPORTED`]: The given suspend type is valid but not
+///     implemented.
+///
+/// [`SbiError::FAILED`]: The suspension request failed for an unknown reason,
+///     or the SBI implementation incorrectly returned success without
+///     suspending the hart.
+pub unsafe fn suspend_non_retentive(
+    kind: NonRetentiveSuspendType,
+) -> Result<core::convert::Infallible, SbiError> {
+    match unsafe { suspend(kind.into()) } {
+        // A successful non-retentive suspend resumes at `resume_address`
+        // rather than returning, so treat a spurious success as a failure
+        Ok(()) => Err(SbiError::FAILED),
+        Err(e) => Err(e),
+    }
+}
+
+/// A retentive suspend type, which saves register and CSR state and restores
+/// those states upon hart resume. See [`suspend_retentive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RetentiveSuspendType {
+    /// Default retentive suspension
+    Default,
+    /// A platform specific retentive suspend type, in the range of
+    /// `0x10000000..=0x7FFFFFFF`.
+    PlatformSpecific(RestrictedRange<0x10000000, 0x7FFFFFFF>),
+}
+
+impl From<RetentiveSuspendType> for SuspendType {
+    fn from(kind: RetentiveSuspendType) -> Self {
+        match kind {
+            RetentiveSuspendType::Default => Self::DefaultRetentive,
+            RetentiveSuspendType::PlatformSpecific(value) => Self::PlatformSpecificRetentive(value),
+        }
+    }
+}
+
+/// A non-retentive suspend type, which does not save any register or CSR
+/// state. The hart will resume execution at `resume_address` with only
+/// registers `a0` and `a1`, and CSRs `satp` and `sstatus.SIE` in a defined
+/// state. See [`suspend_non_retentive`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NonRetentiveSuspendType {
+    /// Default non-retentive suspension
+    Default {
+        /// The address to resume execution at.
+        resume_address: PhysicalAddress<()>,
+        /// User-defined opaque value passed to `resume_address` in `a1` upon
+        /// resumption.
+        opaque: usize,
+    },
+    /// A platform specific non-retentive suspend type
+    PlatformSpecific {
+        /// The platform-specific suspend value, in the range
+        /// `0x90000000..=0xFFFFFFFF`.
+        value: RestrictedRange<0x90000000, 0xFFFFFFFF>,
+        /// The address to resume execution at.
+        resume_address: PhysicalAddress<()>,
+        /// User-defined opaque value passed to `resume_address` in `a1` upon
+        /// resumption.
+        opaque: usize,
+    },
+}
+
+impl From<NonRetentiveSuspendType> for SuspendType {
+    fn from(kind: NonRetentiveSuspendType) -> Self {
+        match kind {
+            NonRetentiveSuspendType::Default {
+                resume_address,
+                opaque,
+            } => Self::DefaultNonRetentive {
+                resume_address,
+                opaque,
+            },
+            NonRetentiveSuspendType::PlatformSpecific {
+                value,
+                resume_address,
+                opaque,
+            } => Self::PlatformSpecificNonRetentive {
+                value,
+                resume_address,
+                opaque,
+            },
+        }
+    }
+}
+
+/// Any type of suspension, combining [`RetentiveSuspendType`] and
+/// [`NonRetentiveSuspendType`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SuspendType {
     /// Default retentive suspension which saves register and CSR state and
     /// restores those states upon hart resume.
@@ -171,6 +395,7 @@ impl SuspendType {
 
 /// Execution state for a hart
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum HartState {
     /// The hart is powered on and executing normally
@@ -189,8 +414,14 @@ pub enum HartState {
     /// An event has caused the hart to begin resuming normal execution
     /// ([`HartState::Started`])
     ResumePending,
+    /// A hart state value not known to this crate
+    Unknown(usize),
 }
 
+/// Deprecated alias of [`HartState`]
+#[deprecated = "use `HartState` instead"]
+pub type HartStatus = HartState;
+
 impl HartState {
     fn from_usize(n: usize) -> Self {
         match n {
@@ -201,7 +432,13 @@ impl HartState {
             4 => HartState::Suspended,
             5 => HartState::SuspendPending,
             6 => HartState::ResumePending,
-            n => unreachable!("invalid hart state returned by SBI: {}", n),
+            n => HartState::Unknown(n),
         }
     }
 }
+
+impl From<usize> for HartState {
+    fn from(value: usize) -> Self {
+        Self::from_usize(value)
+    }
+}
@@ -20,6 +20,43 @@ pub const EXTENSION_ID: usize = 0x735049;
 ///     mode
 #[inline]
 #[doc(alias = "sbi_send_ipi")]
+#[must_use = "a failed IPI request means the target harts were not interrupted"]
 pub fn send_ipi(hart_mask: HartMask) -> Result<(), SbiError> {
     unsafe { ecall2(hart_mask.mask, hart_mask.base, EXTENSION_ID, 0).map(drop) }
 }
+
+/// Send an inter-processor interrupt (IPI) to the single hart `hart_id`. This
+/// is equivalent to calling [`send_ipi`] with `HartMask::from(hart_id)`.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: `hart_id` is invalid or unaccessible from
+///     supervisor mode
+#[inline]
+#[must_use = "a failed IPI request means the target harts were not interrupted"]
+pub fn send_ipi_to(hart_id: usize) -> Result<(), SbiError> {
+    send_ipi(HartMask::from(hart_id))
+}
+
+/// Send an inter-processor interrupt (IPI) to an arbitrarily large set of hart
+/// IDs. The hart IDs are partitioned into the minimum number of [`HartMask`]s
+/// using [`HartMask::windows`] and [`send_ipi`] is called for each, stopping at
+/// the first error. On success, the total number of distinct harts signaled
+/// across all of the [`HartMask`]s is returned, which callers can compare
+/// against the number of harts they expect to be online.
+///
+/// ### Errors
+///
+/// On failure, the error is returned along with the [`HartMask`] for which
+/// the call failed. Harts from that [`HartMask`] onward have not been signaled.
+#[must_use = "a failed IPI request means the target harts were not interrupted"]
+pub fn send_ipi_to_all<I>(hart_ids: I) -> Result<usize, (SbiError, HartMask)>
+where
+    I: IntoIterator<Item = usize>,
+    I::IntoIter: Clone,
+{
+    HartMask::windows(hart_ids.into_iter()).try_fold(0, |signaled, hart_mask| {
+        send_ipi(hart_mask).map_err(|e| (e, hart_mask))?;
+        Ok(signaled + hart_mask.raw_mask().count_ones() as usize)
+    })
+}
@@ -5,6 +5,7 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::HartMask;
 use core::arch::asm;
 
 /// `sbi_set_timer` extension ID
@@ -62,6 +63,18 @@ pub fn console_putchar(c: u8) {
     }
 }
 
+/// A [`core::fmt::Write`] adapter which writes each byte of the formatted
+/// output to the debug console using [`console_putchar`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LegacyConsole;
+
+impl core::fmt::Write for LegacyConsole {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        s.bytes().for_each(console_putchar);
+        Ok(())
+    }
+}
+
 /// `sbi_console_getchar` extension ID
 pub const CONSOLE_GETCHAR_EID: usize = 0x02;
 
@@ -87,6 +100,98 @@ pub fn console_getchar() -> Option<u8> {
     }
 }
 
+/// A fixed-size, allocation-free line buffer over [`console_getchar`] which
+/// accumulates bytes until a newline is read. Input is echoed back to the
+/// console, and a backspace (`0x08` or `0x7F`) removes the last byte of the
+/// line. A `\r\n` pair ends a single line. Lines longer than `N` bytes are
+/// discarded: reading returns [`None`] once the buffer overflows, and the rest
+/// of the line up to its terminator is dropped.
+#[derive(Debug, Clone)]
+pub struct LineReader<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+    line_complete: bool,
+    overflowed: bool,
+    after_cr: bool,
+}
+
+impl<const N: usize> LineReader<N> {
+    /// Create a new, empty [`LineReader`]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; N],
+            len: 0,
+            line_complete: false,
+            overflowed: false,
+            after_cr: false,
+        }
+    }
+
+    /// Read all currently available input from the debug console, returning
+    /// the line without its line terminator once a newline (`\n`, `\r`, or
+    /// `\r\n`) is read. Returns [`None`] if there is no more input available
+    /// before a newline is read, if the line overflows the buffer, or if the
+    /// line is not valid UTF-8.
+    pub fn read_line(&mut self) -> Option<&str> {
+        self.read_line_from(console_getchar, console_putchar)
+    }
+
+    /// Same as [`LineReader::read_line`], but reads bytes from `getchar` and
+    /// echoes them with `echo` instead of using the debug console
+    pub fn read_line_from(
+        &mut self,
+        mut getchar: impl FnMut() -> Option<u8>,
+        mut echo: impl FnMut(u8),
+    ) -> Option<&str> {
+        if self.line_complete {
+            self.line_complete = false;
+            self.len = 0;
+        }
+
+        while let Some(byte) = getchar() {
+            let after_cr = core::mem::replace(&mut self.after_cr, byte == b'\r');
+            match byte {
+                // The `\n` of a `\r\n` pair, the line was already ended by `\r`
+                b'\n' if after_cr => {}
+                b'\n' | b'\r' => {
+                    echo(b'\n');
+                    if core::mem::take(&mut self.overflowed) {
+                        self.len = 0;
+                        continue;
+                    }
+
+                    self.line_complete = true;
+                    return core::str::from_utf8(&self.buffer[..self.len]).ok();
+                }
+                _ if self.overflowed => {}
+                0x08 | 0x7F => {
+                    if self.len > 0 {
+                        self.len -= 1;
+                        [0x08, b' ', 0x08].into_iter().for_each(&mut echo);
+                    }
+                }
+                _ if self.len < N => {
+                    self.buffer[self.len] = byte;
+                    self.len += 1;
+                    echo(byte);
+                }
+                _ => {
+                    self.overflowed = true;
+                    return None;
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<const N: usize> Default for LineReader<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// `sbi_clear_ipi` extension ID
 pub const CLEAR_IPI_EID: usize = 0x03;
 
@@ -126,6 +231,14 @@ pub fn send_ipi(hart_mask: &[usize]) {
     }
 }
 
+/// Send an interprocessor interrupt (IPI) to all of the harts specified by
+/// the given [`LegacyHartMask`]. Use [`LegacyHartMask::new`] to convert a
+/// [`HartMask`] into the legacy bit vector representation.
+#[inline]
+pub fn send_ipi_mask(hart_mask: &LegacyHartMask) {
+    send_ipi(hart_mask.as_slice())
+}
+
 /// `sbi_remote_fence_i` extension ID
 pub const REMOTE_FENCE_I_EID: usize = 0x05;
 
@@ -146,6 +259,14 @@ pub fn remote_fence_i(hart_mask: &[usize]) {
     }
 }
 
+/// Execute a `FENCE.I` instruction on the harts specified by the given
+/// [`LegacyHartMask`]. Use [`LegacyHartMask::new`] to convert a [`HartMask`]
+/// into the legacy bit vector representation.
+#[inline]
+pub fn remote_fence_i_mask(hart_mask: &LegacyHartMask) {
+    remote_fence_i(hart_mask.as_slice())
+}
+
 /// `sbi_remote_sfence_vma` extension ID
 pub const REMOTE_SFENCE_VMA_EID: usize = 0x06;
 
@@ -177,6 +298,14 @@ pub fn remote_sfence_vma(hart_mask: &[usize], start: usize, size: usize) {
     }
 }
 
+/// Execute a `SFENCE.VMA` instruction on the harts specified by the given
+/// [`LegacyHartMask`] for the virtual memory range specified by `start` and
+/// `size`. See [`remote_sfence_vma`] for more information about the arguments.
+#[inline]
+pub fn remote_sfence_vma_mask(hart_mask: &LegacyHartMask, start: usize, size: usize) {
+    remote_sfence_vma(hart_mask.as_slice(), start, size)
+}
+
 /// `sbi_remote_sfence_vma_asid` extension ID
 pub const REMOTE_SFENCE_VMA_ASID_EID: usize = 0x07;
 
@@ -210,6 +339,20 @@ pub fn remote_sfence_vma_asid(hart_mask: &[usize], start: usize, size: usize, as
     }
 }
 
+/// Execute a `SFENCE.VMA` instruction on the harts specified by the given
+/// [`LegacyHartMask`] for the virtual memory range specified by `start` and
+/// `size` for the given Address Space ID (ASID) only. See
+/// [`remote_sfence_vma_asid`] for more information about the arguments.
+#[inline]
+pub fn remote_sfence_vma_asid_mask(
+    hart_mask: &LegacyHartMask,
+    start: usize,
+    size: usize,
+    asid: usize,
+) {
+    remote_sfence_vma_asid(hart_mask.as_slice(), start, size, asid)
+}
+
 /// `sbi_shutdown` extension ID
 pub const SHUTDOWN_EID: usize = 0x08;
 
@@ -227,3 +370,45 @@ pub fn shutdown() -> ! {
         );
     }
 }
+
+const LEGACY_HART_MASK_WORDS: usize = 8;
+
+/// The legacy bit vector representation of a [`HartMask`]. Unlike a
+/// [`HartMask`], which selects harts relative to a base hart ID, the legacy
+/// bit vector always starts at hart ID 0: bit `n` of word `w` selects the hart
+/// ID `w * usize::BITS + n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegacyHartMask {
+    words: [usize; LEGACY_HART_MASK_WORDS],
+    len: usize,
+}
+
+impl LegacyHartMask {
+    /// The number of hart IDs representable by a [`LegacyHartMask`], starting
+    /// at hart ID 0
+    pub const MAX_HARTS: usize = LEGACY_HART_MASK_WORDS * usize::BITS as usize;
+
+    /// Convert the given [`HartMask`] into its legacy bit vector
+    /// representation, returning `None` if any of the selected hart IDs are
+    /// greater than or equal to [`LegacyHartMask::MAX_HARTS`]
+    pub fn new(hart_mask: HartMask) -> Option<Self> {
+        let mut words = [0; LEGACY_HART_MASK_WORDS];
+        let mut len = 1;
+
+        for bit in (0..usize::BITS as usize).filter(|bit| hart_mask.mask & (1 << bit) != 0) {
+            let hart_id = hart_mask.base.checked_add(bit)?;
+            let word = hart_id / usize::BITS as usize;
+
+            *words.get_mut(word)? |= 1 << (hart_id % usize::BITS as usize);
+            len = len.max(word + 1);
+        }
+
+        Some(Self { words, len })
+    }
+
+    /// The bit vector words, with trailing words that contain no selected
+    /// harts omitted
+    pub fn as_slice(&self) -> &[usize] {
+        &self.words[..self.len]
+    }
+}
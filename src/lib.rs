@@ -18,14 +18,26 @@ pub mod base;
 pub mod collaborative_processor_performance_control;
 /// Debug Console extension
 pub mod debug_console;
+/// Debug Triggers extension
+pub mod debug_triggers;
+/// Marker types for the standard SBI extensions
+pub mod extensions;
+/// Firmware Features extension
+pub mod firmware_features;
 /// Hart State Management extension
 pub mod hart_state_management;
 /// IPI extension
 pub mod ipi;
 /// Legacy SBI calls
 pub mod legacy;
+/// Nested Acceleration extension
+pub mod nested_acceleration;
 /// Performance Monitoring Unit extension
 pub mod performance_monitoring_unit;
+/// The most commonly used types, macros, and module aliases, intended to be
+/// glob imported with `use sbi::prelude::*;`. Items specific to a single
+/// extension should be imported from their extension module instead.
+pub mod prelude;
 /// RFENCE extension
 pub mod rfence;
 /// System Reset extension
@@ -41,9 +53,13 @@ use core::{num::NonZeroIsize, ptr::NonNull};
 pub use collaborative_processor_performance_control as cbbc;
 /// A convenience alias to the [`hart_state_management`] module.
 pub use hart_state_management as hsm;
+/// A convenience alias to the [`nested_acceleration`] module.
+pub use nested_acceleration as nacl;
 /// A convenience alias to the [`performance_monitoring_unit`] module;
 pub use performance_monitoring_unit as pmu;
 
+pub use debug_console::emergency_print;
+
 /// Error codes returned by SBI calls
 ///
 /// For all of the various error codes, see the associated constants on this type, such as [`SbiError::FAILED`]
@@ -52,13 +68,14 @@ pub use performance_monitoring_unit as pmu;
 /// so that constructing it based on the returned integer code does not require
 /// panicking in the event that new error codes are added to the specification.
 /// Using associated constants also works to emulate `#[non_exhaustive]` since
-/// it is not possible to publically construct this type, so that any new errors
+/// matching on them always requires a wildcard arm, so that any new errors
 /// won't cause compilation errors in code attempting to handle all errors.
 /// (though that should be pretty uncommon)
 ///
 /// note: `SBI_SUCCESS` is not represented here since this is to be used as the
 /// error type in a `Result`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(transparent)]
 pub struct SbiError(Option<NonZeroIsize>);
 
@@ -79,19 +96,37 @@ impl SbiError {
     pub const ALREADY_STARTED: Self = Self(unsafe { Some(NonZeroIsize::new_unchecked(-7)) });
     /// The resource was previously stopped
     pub const ALREADY_STOPPED: Self = Self(unsafe { Some(NonZeroIsize::new_unchecked(-8)) });
-    /// Shared memory is unavailable
+    /// Shared memory is unavailable, such as when a call which relies on a
+    /// shared memory region is made before the region has been set
     pub const SHARED_MEMORY_UNAVAILABLE: Self =
         Self(unsafe { Some(NonZeroIsize::new_unchecked(-9)) });
 }
 
 impl SbiError {
     #[inline]
-    fn new(n: isize) -> Self {
+    const fn new(n: isize) -> Self {
         match n {
             n if n.is_negative() => Self(Some(unsafe { NonZeroIsize::new_unchecked(n) })),
             _ => Self(None),
         }
     }
+
+    /// Create a [`SbiError`] from the raw error code returned in `a0`. Error
+    /// codes are always negative, so any non-negative code produces an error
+    /// whose raw value is `0`.
+    #[inline]
+    pub const fn from_raw(code: isize) -> Self {
+        Self::new(code)
+    }
+
+    /// The raw error code of this error
+    #[inline]
+    pub const fn as_raw(self) -> isize {
+        match self.0 {
+            Some(code) => code.get(),
+            None => 0,
+        }
+    }
 }
 
 impl core::fmt::Display for SbiError {
@@ -109,14 +144,20 @@ impl core::fmt::Display for SbiError {
                     "SBI call not implemented or functionality not available",
                 SbiError::ALREADY_STARTED => "resource was already started",
                 SbiError::ALREADY_STOPPED => "resource was already stopped",
-                _ => "unknown error",
+                SbiError::SHARED_MEMORY_UNAVAILABLE => "shared memory is unavailable",
+                _ => return write!(f, "unknown SBI error ({})", self.as_raw()),
             }
         )
     }
 }
 
 /// A SBI hart mask
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Two [`HartMask`]s compare equal (and hash identically) if they select the
+/// same set of hart IDs, regardless of their base. For comparison, masks are
+/// put into a canonical form where the base is the lowest selected hart ID, or
+/// a base and mask of `0` if no hart IDs are selected.
+#[derive(Clone, Copy)]
 pub struct HartMask {
     base: usize,
     mask: usize,
@@ -150,6 +191,260 @@ impl HartMask {
 
         self
     }
+
+    /// The base hart ID of this [`HartMask`], as passed to the SBI
+    /// implementation in the `hart_mask_base` parameter. This is the base the
+    /// mask was constructed with, not the canonical base used for comparison.
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    /// The raw mask of selected hart IDs relative to [`HartMask::base`], as
+    /// passed to the SBI implementation in the `hart_mask` parameter
+    #[inline]
+    pub const fn raw_mask(&self) -> usize {
+        self.mask
+    }
+
+    /// Whether the given hart ID is selected
+    #[inline]
+    pub const fn contains(&self, hart_id: usize) -> bool {
+        hart_id >= self.base
+            && hart_id - self.base < usize::BITS as usize
+            && self.mask & (1 << (hart_id - self.base)) != 0
+    }
+
+    /// The canonical `(base, mask)` form of this [`HartMask`], see the
+    /// type-level documentation
+    const fn canonical(&self) -> (usize, usize) {
+        match self.mask {
+            0 => (0, 0),
+            mask => {
+                let shift = mask.trailing_zeros();
+                (self.base.wrapping_add(shift as usize), mask >> shift)
+            }
+        }
+    }
+
+    /// Express the same set of selected hart IDs relative to `new_base`,
+    /// returning [`None`] if any selected hart ID is below `new_base` or at
+    /// least `usize::BITS` IDs above it. An empty [`HartMask`] can always be
+    /// rebased.
+    pub const fn rebased(self, new_base: usize) -> Option<Self> {
+        let (base, mask) = self.canonical();
+        if mask == 0 {
+            return Some(Self::new(new_base));
+        }
+
+        let highest = base + (usize::BITS - 1 - mask.leading_zeros()) as usize;
+        if base < new_base || highest - new_base >= usize::BITS as usize {
+            return None;
+        }
+
+        Some(Self {
+            base: new_base,
+            mask: mask << (base - new_base),
+        })
+    }
+
+    /// A [`HartMask`] selecting the hart IDs selected by either `self` or
+    /// `other`, using the smallest selected hart ID as the base. Returns
+    /// [`None`] if the combined hart IDs span more than `usize::BITS` IDs and
+    /// therefore can't be represented by a single [`HartMask`].
+    pub const fn union(self, other: Self) -> Option<Self> {
+        let (self_base, self_mask) = self.canonical();
+        let (other_base, other_mask) = other.canonical();
+        let (base, mask, high) = match (self_mask, other_mask) {
+            (0, _) => return Some(other),
+            (_, 0) => return Some(self),
+            _ if self_base <= other_base => (self_base, self_mask, other),
+            _ => (other_base, other_mask, self),
+        };
+
+        match high.rebased(base) {
+            Some(high) => Some(Self {
+                base,
+                mask: mask | high.mask,
+            }),
+            None => None,
+        }
+    }
+
+    /// A [`HartMask`] selecting the hart IDs selected by both `self` and
+    /// `other`. Unlike [`HartMask::union`], the result always fits in a single
+    /// [`HartMask`] since it can only select hart IDs that `self` already
+    /// selects.
+    pub const fn intersection(self, other: Self) -> Self {
+        let (self_base, self_mask) = self.canonical();
+        let (other_base, other_mask) = other.canonical();
+        let (base, high_mask, low_mask, shift) = if self_base >= other_base {
+            (self_base, self_mask, other_mask, self_base - other_base)
+        } else {
+            (other_base, other_mask, self_mask, other_base - self_base)
+        };
+
+        if shift >= usize::BITS as usize {
+            return Self::new(0);
+        }
+
+        Self {
+            base,
+            mask: high_mask & (low_mask >> shift),
+        }
+    }
+
+    /// Create a new [`HartMask`] from an iterator of hart IDs, using the
+    /// smallest hart ID as the base.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if the hart IDs span more than `usize::BITS`
+    /// IDs and therefore can't be represented by a single [`HartMask`]. See
+    /// [`HartMask::try_from_ids`] for a non-panicking version.
+    #[inline]
+    pub fn from_ids(ids: impl IntoIterator<Item = usize>) -> Self {
+        match Self::try_from_ids(ids) {
+            Ok(hart_mask) => hart_mask,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Create a new [`HartMask`] from an iterator of hart IDs, using the
+    /// smallest hart ID as the base. If no hart IDs are given, the returned
+    /// [`HartMask`] has a base of `0` and no hart IDs selected.
+    ///
+    /// ### Errors
+    ///
+    /// Returns a [`HartMaskOverflow`] containing the first hart ID which could
+    /// not be represented if the hart IDs span more than `usize::BITS` IDs.
+    pub fn try_from_ids(ids: impl IntoIterator<Item = usize>) -> Result<Self, HartMaskOverflow> {
+        let mut ids = ids.into_iter();
+        let mut hart_mask = match ids.next() {
+            Some(hart_id) => Self::from(hart_id),
+            None => return Ok(Self::new(0)),
+        };
+
+        for hart_id in ids {
+            if hart_id < hart_mask.base {
+                // Lower the base so that it remains the smallest hart ID seen,
+                // which requires that the currently selected hart IDs still fit
+                // in the window after shifting
+                let shift = hart_mask.base - hart_id;
+                if shift >= usize::BITS as usize || hart_mask.mask.leading_zeros() < shift as u32 {
+                    return Err(HartMaskOverflow { hart_id });
+                }
+
+                hart_mask.mask = (hart_mask.mask << shift) | 1;
+                hart_mask.base = hart_id;
+            } else if hart_id - hart_mask.base < usize::BITS as usize {
+                hart_mask.mask |= 1 << (hart_id - hart_mask.base);
+            } else {
+                return Err(HartMaskOverflow { hart_id });
+            }
+        }
+
+        Ok(hart_mask)
+    }
+
+    /// Partition the given hart IDs into the minimum number of [`HartMask`]s
+    /// required to select all of them, returned in ascending order of their
+    /// base hart ID. The hart IDs do not need to be sorted and may contain
+    /// duplicates. No allocation is performed: instead, `hart_ids` is cloned
+    /// and walked twice for each returned [`HartMask`].
+    pub fn windows<I>(hart_ids: I) -> impl Iterator<Item = Self>
+    where
+        I: Iterator<Item = usize> + Clone,
+    {
+        let mut lowest = Some(0);
+        core::iter::from_fn(move || {
+            let lowest_remaining = lowest?;
+            let base = hart_ids
+                .clone()
+                .filter(|&hart_id| hart_id >= lowest_remaining)
+                .min()?;
+            let mask = hart_ids
+                .clone()
+                .filter(|&hart_id| hart_id >= base && hart_id - base < usize::BITS as usize)
+                .fold(0, |mask, hart_id| mask | (1 << (hart_id - base)));
+
+            lowest = base.checked_add(usize::BITS as usize);
+            Some(Self { base, mask })
+        })
+    }
+}
+
+impl PartialEq for HartMask {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for HartMask {}
+
+impl core::hash::Hash for HartMask {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+impl core::ops::BitOr for HartMask {
+    type Output = Option<Self>;
+
+    /// See [`HartMask::union`]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl core::ops::BitAnd for HartMask {
+    type Output = Self;
+
+    /// See [`HartMask::intersection`]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl core::fmt::Debug for HartMask {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct SelectedHarts(HartMask);
+
+        impl core::fmt::Debug for SelectedHarts {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_list()
+                    .entries(
+                        (0..usize::BITS as usize)
+                            .filter(|bit| self.0.mask & (1 << bit) != 0)
+                            .map(|bit| self.0.base + bit),
+                    )
+                    .finish()
+            }
+        }
+
+        f.debug_struct("HartMask")
+            .field("base", &self.base)
+            .field("harts", &SelectedHarts(*self))
+            .finish()
+    }
+}
+
+/// Error returned when a set of hart IDs spans more than `usize::BITS` IDs and
+/// can't be represented by a single [`HartMask`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HartMaskOverflow {
+    /// The hart ID which could not be represented
+    pub hart_id: usize,
+}
+
+impl core::fmt::Display for HartMaskOverflow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "hart ID {} does not fit in a single hart mask",
+            self.hart_id
+        )
+    }
 }
 
 /// A convenience macro to help create a [`HartMask`] from either one or more
@@ -176,7 +471,31 @@ macro_rules! hart_mask {
     }};
 }
 
+/// Create a [`RestrictedRange`] value, checking that the value is within the
+/// range of the type at compile time. An out-of-range value is a compilation
+/// error rather than a runtime panic, which makes this macro preferable to
+/// [`RestrictedRange::new`] when the value is a constant.
+///
+/// Examples:
+///
+/// ```rust,ignore
+/// // Checked at compile time
+/// let value = restricted_range!(RestrictedRange<0x10000000, 0x7FFFFFFF>, 0x10000000);
+///
+/// // Fails to compile, since the value is outside of the range
+/// let value = restricted_range!(RestrictedRange<0x10000000, 0x7FFFFFFF>, 0x80000000);
+/// ```
+#[macro_export]
+macro_rules! restricted_range {
+    ($range:ty, $value:expr $(,)?) => {
+        const { <$range>::new($value) }
+    };
+}
+
 /// A value restricted to a given range
+///
+/// To check constant values at compile time, use the [`restricted_range!`]
+/// macro.
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct RestrictedRange<const MIN: u32, const MAX: u32>(u32);
@@ -197,6 +516,18 @@ impl<const MIN: u32, const MAX: u32> RestrictedRange<MIN, MAX> {
     }
 }
 
+impl<const MIN: u32, const MAX: u32> RestrictedRange<MIN, MAX> {
+    /// Create a new [`RestrictedRange`] value, returning [`None`] if the
+    /// provided value is outside of the range of the type
+    pub const fn try_new(value: u32) -> Option<Self> {
+        if value < MIN || value > MAX {
+            return None;
+        }
+
+        Some(Self(value))
+    }
+}
+
 impl<const MIN: u32, const MAX: u32> From<RestrictedRange<MIN, MAX>> for u32 {
     fn from(value: RestrictedRange<MIN, MAX>) -> Self {
         value.0
@@ -221,6 +552,33 @@ impl<const MIN: u32, const MAX: u32> core::fmt::Debug for RestrictedRange<MIN, M
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<const MIN: u32, const MAX: u32> defmt::Format for RestrictedRange<MIN, MAX> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "RestrictedRange<MIN={=u32:#X}, MAX={=u32:#X}>({=u32:#X})",
+            MIN,
+            MAX,
+            self.0
+        )
+    }
+}
+
+/// Error returned when converting a raw value which is reserved by the SBI
+/// specification into a typed value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservedValue {
+    /// The reserved value
+    pub value: u32,
+}
+
+impl core::fmt::Display for ReservedValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "value {:#X} is reserved", self.value)
+    }
+}
+
 /// Representation of a physical address
 #[repr(transparent)]
 pub struct PhysicalAddress<T: ?Sized>(*mut T);
@@ -231,6 +589,13 @@ impl<T: ?Sized> core::fmt::Debug for PhysicalAddress<T> {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl<T: ?Sized> defmt::Format for PhysicalAddress<T> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=usize:#x}", self.0.cast::<u8>() as usize)
+    }
+}
+
 impl<T: ?Sized> Copy for PhysicalAddress<T> {}
 impl<T: ?Sized> Clone for PhysicalAddress<T> {
     fn clone(&self) -> Self {
@@ -270,6 +635,13 @@ impl<T: ?Sized> PhysicalAddress<T> {
     pub fn from_ptr(ptr: *mut T) -> Self {
         Self(ptr)
     }
+
+    /// Reinterpret the [`PhysicalAddress`] as pointing to a `U`, preserving
+    /// the raw address. Any slice length metadata is discarded.
+    #[inline]
+    pub fn cast<U>(self) -> PhysicalAddress<U> {
+        PhysicalAddress(self.0.cast())
+    }
 }
 
 impl<T: Sized> PhysicalAddress<T> {
@@ -543,3 +915,330 @@ pub unsafe fn ecall6(
         e => Result::Err(SbiError::new(e)),
     }
 }
+
+/// A zero-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall0`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts no
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+pub unsafe fn ecall0_2(
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        lateout("a0") error,
+        lateout("a1") value,
+        lateout("a2") value2,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A one-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall1`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts one
+/// parameter and returns two values, otherwise the behavior is undefined.
+#[inline]
+pub unsafe fn ecall1_2(
+    arg0: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        lateout("a1") value,
+        lateout("a2") value2,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A two-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall2`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts two
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+pub unsafe fn ecall2_2(
+    arg0: usize,
+    arg1: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => value,
+        lateout("a2") value2,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A three-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall3`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts three
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+pub unsafe fn ecall3_2(
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => value,
+        inlateout("a2") arg2 => value2,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A four-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall4`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts four
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+pub unsafe fn ecall4_2(
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => value,
+        inlateout("a2") arg2 => value2,
+        in("a3") arg3,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A five-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall5`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts five
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn ecall5_2(
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
+    arg4: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => value,
+        inlateout("a2") arg2 => value2,
+        in("a3") arg3,
+        in("a4") arg4,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// A six-argument `ecall` with the given extension and function IDs,
+/// which returns both the `a1` and `a2` registers on success, for SBI
+/// functions which return two values. See [`ecall6`] for more information.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts six
+/// parameters and returns two values, otherwise the behavior is undefined.
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn ecall6_2(
+    arg0: usize,
+    arg1: usize,
+    arg2: usize,
+    arg3: usize,
+    arg4: usize,
+    arg5: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> Result<(usize, usize), SbiError> {
+    let error: isize;
+    let value: usize;
+    let value2: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") arg0 => error,
+        inlateout("a1") arg1 => value,
+        inlateout("a2") arg2 => value2,
+        in("a3") arg3,
+        in("a4") arg4,
+        in("a5") arg5,
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    match error {
+        0 => Result::Ok((value, value2)),
+        e => Result::Err(SbiError::new(e)),
+    }
+}
+
+/// An `ecall` with the given extension and function IDs, passing up to six
+/// arguments from `args` in registers `a0` through `a5`. This dispatches to
+/// the fixed-arity `ecall` function matching the length of `args`, such as
+/// [`ecall3`] for a three element slice, so it compiles down to the
+/// fixed-arity path when the length is known.
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts exactly
+/// `args.len()` parameters, otherwise the behavior is undefined, as the
+/// additional argument registers will have undefined contents when passed to
+/// the SBI implementation.
+///
+/// # Panics
+/// This function panics if more than six arguments are supplied.
+#[inline]
+pub unsafe fn ecall(
+    args: &[usize],
+    extension_id: usize,
+    function_id: usize,
+) -> Result<usize, SbiError> {
+    match *args {
+        [] => ecall0(extension_id, function_id),
+        [a0] => ecall1(a0, extension_id, function_id),
+        [a0, a1] => ecall2(a0, a1, extension_id, function_id),
+        [a0, a1, a2] => ecall3(a0, a1, a2, extension_id, function_id),
+        [a0, a1, a2, a3] => ecall4(a0, a1, a2, a3, extension_id, function_id),
+        [a0, a1, a2, a3, a4] => ecall5(a0, a1, a2, a3, a4, extension_id, function_id),
+        [a0, a1, a2, a3, a4, a5] => ecall6(a0, a1, a2, a3, a4, a5, extension_id, function_id),
+        _ => panic!("SBI calls accept at most six arguments, got {}", args.len()),
+    }
+}
+
+/// The lowest level `ecall` primitive, passing the first `argc` elements of
+/// `args` in registers `a0` through `a5` (the remaining argument registers are
+/// zeroed) and returning the raw error code in `a0` and value in `a1` without
+/// interpreting them. Unlike the other `ecall` functions, the value in `a1` is
+/// returned even when the call fails, which is useful for calling SBI functions
+/// this crate doesn't wrap, such as new or vendor-specific extensions, that
+/// return additional information on error. The error code can be converted
+/// with [`SbiError::from_raw`].
+///
+/// # Safety
+/// This function is only safe to call if the given function ID accepts `argc`
+/// parameters, and any memory the SBI implementation may access as a result of
+/// the call is valid to be accessed.
+///
+/// # Panics
+/// This function panics if `argc` is greater than six.
+#[inline]
+pub unsafe fn raw_ecall(
+    args: [usize; 6],
+    argc: usize,
+    extension_id: usize,
+    function_id: usize,
+) -> (isize, usize) {
+    assert!(
+        argc <= 6,
+        "SBI calls accept at most six arguments, got {argc}"
+    );
+
+    let mut regs = [0; 6];
+    regs[..argc].copy_from_slice(&args[..argc]);
+
+    let error: isize;
+    let value: usize;
+
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") regs[0] => error,
+        inlateout("a1") regs[1] => value,
+        in("a2") regs[2],
+        in("a3") regs[3],
+        in("a4") regs[4],
+        in("a5") regs[5],
+        in("a6") function_id,
+        in("a7") extension_id,
+    );
+
+    (error, value)
+}
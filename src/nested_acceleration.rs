@@ -5,7 +5,7 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{ecall1, ecall3, PhysicalAddress, SbiError};
+use crate::{ecall0, ecall1, ecall3, PhysicalAddress, SbiError};
 
 /// Nested Acceleration extension ID
 pub const EXTENSION_ID: usize = 0x4E41434C;
@@ -14,11 +14,15 @@ mod sealed {
     pub trait Sealed {}
 }
 
+/// The address of a hypervisor (H-extension) or virtual supervisor (VS-mode)
+/// CSR which is accessible through the nested acceleration shared memory
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct CsrAddress(u16);
 
 impl CsrAddress {
+    /// Create a new [`CsrAddress`], returning [`None`] if `raw` is not within
+    /// one of the CSR address ranges mapped into the shared memory CSR space
     pub const fn new(raw: u16) -> Option<Self> {
         match raw {
             0x200..=0x2FF
@@ -35,15 +39,42 @@ impl CsrAddress {
         }
     }
 
+    /// The index of the CSR's slot in the shared memory CSR space. The sparse
+    /// CSR address space is compressed into 1024 slots by keeping the
+    /// privilege bits `[11:10]` and the low bits `[7:0]` of the address, such
+    /// that the `0x200`, `0x600`, `0xA00`, and `0xE00` CSR banks begin at the
+    /// indices `0x000`, `0x100`, `0x200`, and `0x300` respectively.
+    pub const fn shmem_index(self) -> usize {
+        (((self.0 & 0xC00) >> 2) | (self.0 & 0xFF)) as usize
+    }
+
+    /// Create a new [`CsrAddress`] without checking that `raw` is within one
+    /// of the CSR address ranges mapped into the shared memory CSR space.
+    /// Using an invalid address will cause the SBI implementation to return an
+    /// error when synchronizing the CSR.
     pub const fn new_unchecked(raw: u16) -> Self {
         Self(raw)
     }
+
+    /// The raw CSR address
+    pub const fn as_u16(self) -> u16 {
+        self.0
+    }
 }
 
+/// The CSR address ranges mapped into the shared memory CSR space, which
+/// [`CsrAddress::new`] accepts
+pub const VALID_CSR_ADDRESS_RANGES: [core::ops::RangeInclusive<u16>; 4] =
+    [0x200..=0x2FF, 0x600..=0x6FF, 0xA00..=0xAFF, 0xE00..=0xEFF];
+
+/// A CSR which is accessible through the nested acceleration shared memory
 pub trait HExtensionCsr: Sized + Copy {
+    /// The address of the CSR
     const ADDRESS: CsrAddress;
 }
 
+/// The CSR(s) to synchronize with [`synchronize_csr`], either a single
+/// [`CsrAddress`] or [`UPDATE_ALL_CSRS`]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct UpdateCsrAddress(u16);
@@ -54,11 +85,18 @@ impl From<CsrAddress> for UpdateCsrAddress {
     }
 }
 
+/// Synchronize all of the CSRs in the shared memory CSR space
 pub const UPDATE_ALL_CSRS: UpdateCsrAddress = UpdateCsrAddress(u16::MAX);
 
+/// Synchronize all of the entries in the [`SynchronizeHfence`] feature region
+/// when passed to [`synchronize_hfence`]
+pub const UPDATE_ALL_HFENCE_ENTRIES: usize = usize::MAX;
+
+/// The CSRs which are accessible through the nested acceleration shared memory
 pub mod csrs {
     use super::CsrAddress;
 
+    /// The `hstatus` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hstatus(usize);
@@ -66,6 +104,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x600);
     }
 
+    /// The `hedeleg` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hedeleg(usize);
@@ -73,6 +112,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x602);
     }
 
+    /// The `hideleg` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hideleg(usize);
@@ -80,6 +120,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x603);
     }
 
+    /// The `hie` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hie(usize);
@@ -87,6 +128,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x604);
     }
 
+    /// The `hcounteren` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hcounteren(usize);
@@ -94,6 +136,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x606);
     }
 
+    /// The `hgeie` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hgeie(usize);
@@ -101,6 +144,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x607);
     }
 
+    /// The `htval` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Htval(usize);
@@ -108,6 +152,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x643);
     }
 
+    /// The `hip` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hip(usize);
@@ -115,6 +160,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x644);
     }
 
+    /// The `hvip` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hvip(usize);
@@ -122,6 +168,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x645);
     }
 
+    /// The `htinst` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Htinst(usize);
@@ -129,6 +176,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x64A);
     }
 
+    /// The `hgeip` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hgeip(usize);
@@ -136,6 +184,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0xE12);
     }
 
+    /// The `henvcfg` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Henvcfg(usize);
@@ -143,6 +192,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x60A);
     }
 
+    /// The `henvcfgh` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Henvcfgh(usize);
@@ -150,6 +200,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x61A);
     }
 
+    /// The `hgatp` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hgatp(usize);
@@ -157,6 +208,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x680);
     }
 
+    /// The `hcontext` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Hcontext(usize);
@@ -164,6 +216,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x6A8);
     }
 
+    /// The `htimedelta` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Htimedelta(usize);
@@ -171,6 +224,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x605);
     }
 
+    /// The `htimedeltah` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Htimedeltah(usize);
@@ -178,6 +232,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x615);
     }
 
+    /// The `vsstatus` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsstatus(usize);
@@ -185,6 +240,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x200);
     }
 
+    /// The `vsie` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsie(usize);
@@ -192,6 +248,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x204);
     }
 
+    /// The `vstvec` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vstvec(usize);
@@ -199,6 +256,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x205);
     }
 
+    /// The `vsscratch` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsscratch(usize);
@@ -206,6 +264,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x240);
     }
 
+    /// The `vsepc` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsepc(usize);
@@ -213,6 +272,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x241);
     }
 
+    /// The `vscause` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vscause(usize);
@@ -220,6 +280,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x242);
     }
 
+    /// The `vstval` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vstval(usize);
@@ -227,6 +288,7 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x243);
     }
 
+    /// The `vsip` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsip(usize);
@@ -234,19 +296,189 @@ pub mod csrs {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x244);
     }
 
+    /// The `vsatp` CSR
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[repr(transparent)]
     pub struct Vsatp(usize);
     impl super::HExtensionCsr for Vsatp {
         const ADDRESS: CsrAddress = CsrAddress::new_unchecked(0x280);
     }
+
+    macro_rules! csr_bits {
+        ($($name:ident),+ $(,)?) => {
+            $(
+                impl $name {
+                    /// Create the CSR value from its raw bits
+                    #[inline]
+                    pub const fn from_bits(bits: usize) -> Self {
+                        Self(bits)
+                    }
+
+                    /// The raw bits of the CSR value
+                    #[inline]
+                    pub const fn bits(self) -> usize {
+                        self.0
+                    }
+                }
+            )+
+        };
+    }
+
+    csr_bits! {
+        Hstatus,
+        Hedeleg,
+        Hideleg,
+        Hie,
+        Hcounteren,
+        Hgeie,
+        Htval,
+        Hip,
+        Hvip,
+        Htinst,
+        Hgeip,
+        Henvcfg,
+        Henvcfgh,
+        Hgatp,
+        Hcontext,
+        Htimedelta,
+        Htimedeltah,
+        Vsstatus,
+        Vsie,
+        Vstvec,
+        Vsscratch,
+        Vsepc,
+        Vscause,
+        Vstval,
+        Vsip,
+        Vsatp,
+    }
+
+    const fn bit(bits: usize, n: u32) -> bool {
+        bits & (1 << n) != 0
+    }
+
+    impl Hstatus {
+        /// The `VSBE` field: whether explicit memory accesses made from VS-mode
+        /// are big-endian
+        #[inline]
+        pub const fn vsbe(self) -> bool {
+            bit(self.0, 5)
+        }
+
+        /// The `GVA` field: whether a guest virtual address was written to
+        /// `stval` by the last trap into HS-mode
+        #[inline]
+        pub const fn gva(self) -> bool {
+            bit(self.0, 6)
+        }
+
+        /// The `SPV` field: whether the virtualization mode was enabled before
+        /// the last trap into HS-mode
+        #[inline]
+        pub const fn spv(self) -> bool {
+            bit(self.0, 7)
+        }
+
+        /// The `SPVP` field: whether the privilege mode was supervisor before
+        /// the last trap into HS-mode from VS-mode or VU-mode
+        #[inline]
+        pub const fn spvp(self) -> bool {
+            bit(self.0, 8)
+        }
+
+        /// The `HU` field: whether hypervisor virtual-machine load and store
+        /// instructions can be used in U-mode
+        #[inline]
+        pub const fn hu(self) -> bool {
+            bit(self.0, 9)
+        }
+
+        /// The `VGEIN` field: the guest external interrupt number selected
+        /// for VS-level external interrupts
+        #[inline]
+        pub const fn vgein(self) -> usize {
+            (self.0 >> 12) & 0x3F
+        }
+
+        /// The `VTVM` field: whether VS-mode `SFENCE.VMA`, `SINVAL.VMA`, and
+        /// `satp` accesses raise a virtual instruction exception
+        #[inline]
+        pub const fn vtvm(self) -> bool {
+            bit(self.0, 20)
+        }
+
+        /// The `VTW` field: whether `WFI` in VS-mode raises a virtual
+        /// instruction exception
+        #[inline]
+        pub const fn vtw(self) -> bool {
+            bit(self.0, 21)
+        }
+
+        /// The `VTSR` field: whether `SRET` in VS-mode raises a virtual
+        /// instruction exception
+        #[inline]
+        pub const fn vtsr(self) -> bool {
+            bit(self.0, 22)
+        }
+    }
+
+    impl Vsstatus {
+        /// The `SIE` field: whether VS-level interrupts are enabled
+        #[inline]
+        pub const fn sie(self) -> bool {
+            bit(self.0, 1)
+        }
+
+        /// The `SPIE` field: whether VS-level interrupts were enabled before
+        /// the last trap into VS-mode
+        #[inline]
+        pub const fn spie(self) -> bool {
+            bit(self.0, 5)
+        }
+
+        /// The `SPP` field: whether the privilege mode was VS-mode (rather
+        /// than VU-mode) before the last trap into VS-mode
+        #[inline]
+        pub const fn spp(self) -> bool {
+            bit(self.0, 8)
+        }
+
+        /// The `SUM` field: whether VS-mode memory accesses to user pages are
+        /// permitted
+        #[inline]
+        pub const fn sum(self) -> bool {
+            bit(self.0, 18)
+        }
+
+        /// The `MXR` field: whether loads from executable pages are permitted
+        #[inline]
+        pub const fn mxr(self) -> bool {
+            bit(self.0, 19)
+        }
+    }
 }
 
+/// A value in the nested acceleration shared memory which may be concurrently
+/// accessed by the SBI implementation, and therefore must only be accessed
+/// through the volatile operations provided by [`VolatileOps`]
 #[repr(transparent)]
 pub struct Volatile<T: Copy>(T);
 
+/// Volatile operations on pointers to values in the nested acceleration shared
+/// memory
 pub trait VolatileOps<T: Copy> {
+    /// Perform a volatile read of the value
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must be valid for reads and properly aligned.
     unsafe fn volatile_read(self) -> T;
+
+    /// Perform a volatile write of the value
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must be valid for writes and properly aligned.
     unsafe fn volatile_write(self, value: T);
 }
 
@@ -260,101 +492,562 @@ impl<T: Copy> VolatileOps<T> for *mut Volatile<T> {
     }
 }
 
+/// A nested acceleration feature which can be probed with [`probe_feature`]
 pub trait NaclFeature: sealed::Sealed {
+    /// The feature ID
     const ID: u32;
 }
 
+/// The layout of the nested acceleration shared memory, consisting of a
+/// scratch space which contains the feature-specific regions followed by the
+/// CSR space
 #[repr(C, align(4096))]
 pub struct SharedMemoryLayout {
-    scratch_space: [u8; 4096],
-    csr_space: [usize; 128],
+    scratch_space: [u8; SCRATCH_SPACE_SIZE],
+    csr_space: [usize; NUM_CSR_SPACE_ENTRIES],
+}
+
+const SCRATCH_SPACE_SIZE: usize = 4096;
+const NUM_CSR_SPACE_ENTRIES: usize = 1024;
+
+const _: () = assert!(
+    core::mem::size_of::<SharedMemoryLayout>()
+        == SCRATCH_SPACE_SIZE + NUM_CSR_SPACE_ENTRIES * core::mem::size_of::<usize>()
+);
+
+/// Returns a pointer to the feature region `F` at the byte offset `offset`
+/// into the scratch space of the shared memory
+///
+/// ### Safety
+///
+/// The pointer must point to a valid [`SharedMemoryLayout`].
+unsafe fn scratch_space_region<F>(shmem: *mut SharedMemoryLayout, offset: usize) -> *mut F {
+    unsafe {
+        core::ptr::addr_of_mut!((*shmem).scratch_space)
+            .cast::<u8>()
+            .add(offset)
+            .cast::<F>()
+    }
 }
 
+/// Access to the CSR values in the shared memory CSR space
 pub trait CsrSpace {
-    fn index<C: HExtensionCsr>(self, csr: C) -> *mut Volatile<C>;
+    /// Returns a pointer to the shared memory slot of the CSR `C`
+    fn index<C: HExtensionCsr>(self) -> *mut Volatile<C>;
+
+    /// Read the value of the CSR `C` from the shared memory CSR space
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
+    unsafe fn read_csr<C: HExtensionCsr>(self) -> C;
+
+    /// Write the value of the CSR `C` to the shared memory CSR space. The
+    /// change is not visible to the SBI implementation until the CSR is
+    /// synchronized, either by marking it in the [`SynchronizeCsr`] bitmap or
+    /// by calling [`synchronize_csr`].
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
+    unsafe fn write_csr<C: HExtensionCsr>(self, value: C);
+}
+
+impl CsrSpace for *mut SharedMemoryLayout {
+    fn index<C: HExtensionCsr>(self) -> *mut Volatile<C> {
+        const { assert!(core::mem::size_of::<C>() == core::mem::size_of::<usize>()) };
+
+        self.wrapping_byte_add(
+            core::mem::offset_of!(SharedMemoryLayout, csr_space)
+                + C::ADDRESS.shmem_index() * core::mem::size_of::<usize>(),
+        )
+        .cast::<Volatile<C>>()
+    }
+
+    unsafe fn read_csr<C: HExtensionCsr>(self) -> C {
+        unsafe { self.index::<C>().volatile_read() }
+    }
+
+    unsafe fn write_csr<C: HExtensionCsr>(self, value: C) {
+        unsafe { self.index::<C>().volatile_write(value) }
+    }
 }
 
+/// The synchronize CSR feature, consisting of a bitmap of CSRs in the shared
+/// memory CSR space which have been modified and need to be synchronized by
+/// the SBI implementation
 #[repr(transparent)]
 pub struct SynchronizeCsr([u8; 128]);
 
 const _: () = assert!(core::mem::size_of::<SynchronizeCsr>() == 128);
 
+const SYNCHRONIZE_CSR_OFFSET: usize = 0x0F80;
+
+const _: () =
+    assert!(SYNCHRONIZE_CSR_OFFSET + core::mem::size_of::<SynchronizeCsr>() <= SCRATCH_SPACE_SIZE);
+
 impl sealed::Sealed for SynchronizeCsr {}
 impl NaclFeature for SynchronizeCsr {
     const ID: u32 = 0x00000000;
 }
 
+/// Access to the [`SynchronizeCsr`] feature region of the shared memory
 pub trait SynchronizeCsrFeature {
+    /// Returns a pointer to the [`SynchronizeCsr`] feature region
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
     unsafe fn synchronize_csr(self) -> *mut SynchronizeCsr;
 }
 
 impl SynchronizeCsrFeature for *mut SharedMemoryLayout {
     unsafe fn synchronize_csr(self) -> *mut SynchronizeCsr {
-        unsafe {
-            core::ptr::addr_of_mut!((*self).scratch_space)
-                .offset(0x0F80)
-                .cast::<SynchronizeCsr>()
-        }
+        unsafe { scratch_space_region(self, SYNCHRONIZE_CSR_OFFSET) }
     }
 }
 
-const NUM_HFENCE_ENTRIES: usize = 1920 / core::mem::size_of::<usize>() / 4;
+/// The number of [`HfenceEntry`]s in the [`SynchronizeHfence`] feature region
+pub const NUM_HFENCE_ENTRIES: usize = 1920 / core::mem::size_of::<usize>() / 4;
 
+/// The synchronize HFENCE feature, consisting of an array of HFENCE requests
+/// to be processed by the SBI implementation
 #[repr(transparent)]
 pub struct SynchronizeHfence([[usize; 4]; NUM_HFENCE_ENTRIES]);
 
 const _: () = assert!(core::mem::size_of::<SynchronizeHfence>() == 1920);
 
+const SYNCHRONIZE_HFENCE_OFFSET: usize = 0x0800;
+
+const _: () = assert!(
+    SYNCHRONIZE_HFENCE_OFFSET + core::mem::size_of::<SynchronizeHfence>() <= SCRATCH_SPACE_SIZE
+);
+
 impl sealed::Sealed for SynchronizeHfence {}
 impl NaclFeature for SynchronizeHfence {
     const ID: u32 = 0x00000001;
 }
 
+/// Access to the [`SynchronizeHfence`] feature region of the shared memory
 pub trait SynchronizeHfenceFeature {
+    /// Returns a pointer to the [`SynchronizeHfence`] feature region
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
     unsafe fn synchronize_hfence(self) -> *mut SynchronizeHfence;
 }
 
 impl SynchronizeHfenceFeature for *mut SharedMemoryLayout {
     unsafe fn synchronize_hfence(self) -> *mut SynchronizeHfence {
+        unsafe { scratch_space_region(self, SYNCHRONIZE_HFENCE_OFFSET) }
+    }
+}
+
+/// The type of an [`HfenceEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HfenceType {
+    /// `HFENCE.GVMA` for the given guest physical pages
+    Gvma,
+    /// `HFENCE.GVMA` for all guest physical addresses
+    GvmaAll,
+    /// `HFENCE.GVMA` for the given guest physical pages and VMID
+    GvmaVmid,
+    /// `HFENCE.GVMA` for all guest physical addresses of the given VMID
+    GvmaVmidAll,
+    /// `HFENCE.VVMA` for the given guest virtual pages
+    Vvma,
+    /// `HFENCE.VVMA` for all guest virtual addresses
+    VvmaAll,
+    /// `HFENCE.VVMA` for the given guest virtual pages and ASID
+    VvmaAsid,
+    /// `HFENCE.VVMA` for all guest virtual addresses of the given ASID
+    VvmaAsidAll,
+    /// A reserved HFENCE type
+    Reserved(u8),
+}
+
+impl HfenceType {
+    const fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => Self::Gvma,
+            1 => Self::GvmaAll,
+            2 => Self::GvmaVmid,
+            3 => Self::GvmaVmidAll,
+            4 => Self::Vvma,
+            5 => Self::VvmaAll,
+            6 => Self::VvmaAsid,
+            7 => Self::VvmaAsidAll,
+            n => Self::Reserved(n),
+        }
+    }
+
+    const fn to_raw(self) -> u8 {
+        match self {
+            Self::Gvma => 0,
+            Self::GvmaAll => 1,
+            Self::GvmaVmid => 2,
+            Self::GvmaVmidAll => 3,
+            Self::Vvma => 4,
+            Self::VvmaAll => 5,
+            Self::VvmaAsid => 6,
+            Self::VvmaAsidAll => 7,
+            Self::Reserved(n) => n,
+        }
+    }
+}
+
+/// A single HFENCE request in the [`SynchronizeHfence`] feature region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HfenceEntry {
+    /// Whether the HFENCE request is pending. Set by supervisor software to
+    /// submit the request, and cleared by the SBI implementation once the
+    /// request has been processed.
+    pub pending: bool,
+    /// The type of the HFENCE request
+    pub kind: HfenceType,
+    /// The page order of the HFENCE request, where the page size is
+    /// `2^(order + 12)` bytes
+    pub order: u8,
+    /// The VMID of the HFENCE request
+    pub vmid: usize,
+    /// The ASID of the HFENCE request
+    pub asid: usize,
+    /// The first page number of the HFENCE request
+    pub page_number: usize,
+    /// The number of pages covered by the HFENCE request
+    pub page_count: usize,
+}
+
+impl HfenceEntry {
+    const PENDING_SHIFT: u32 = usize::BITS - 1;
+    const TYPE_SHIFT: u32 = usize::BITS - 8;
+    const TYPE_MASK: usize = 0xF;
+    const ORDER_SHIFT: u32 = usize::BITS - 16;
+    const ORDER_MASK: usize = 0x7F;
+    #[cfg(target_arch = "riscv64")]
+    const ASID_BITS: u32 = 16;
+    #[cfg(target_arch = "riscv32")]
+    const ASID_BITS: u32 = 9;
+    #[cfg(target_arch = "riscv64")]
+    const VMID_BITS: u32 = 14;
+    #[cfg(target_arch = "riscv32")]
+    const VMID_BITS: u32 = 7;
+
+    /// Encode the control word of the entry. Out of range fields are
+    /// truncated to the width of their bitfield.
+    pub const fn config(&self) -> usize {
+        ((self.pending as usize) << Self::PENDING_SHIFT)
+            | ((self.kind.to_raw() as usize & Self::TYPE_MASK) << Self::TYPE_SHIFT)
+            | ((self.order as usize & Self::ORDER_MASK) << Self::ORDER_SHIFT)
+            | ((self.vmid & ((1 << Self::VMID_BITS) - 1)) << Self::ASID_BITS)
+            | (self.asid & ((1 << Self::ASID_BITS) - 1))
+    }
+
+    /// Decode an entry from its control word, page number, and page count
+    pub const fn from_raw(config: usize, page_number: usize, page_count: usize) -> Self {
+        Self {
+            pending: config >> Self::PENDING_SHIFT != 0,
+            kind: HfenceType::from_raw(((config >> Self::TYPE_SHIFT) & Self::TYPE_MASK) as u8),
+            order: ((config >> Self::ORDER_SHIFT) & Self::ORDER_MASK) as u8,
+            vmid: (config >> Self::ASID_BITS) & ((1 << Self::VMID_BITS) - 1),
+            asid: config & ((1 << Self::ASID_BITS) - 1),
+            page_number,
+            page_count,
+        }
+    }
+}
+
+/// Access to the individual [`HfenceEntry`]s of the [`SynchronizeHfence`]
+/// feature region
+pub trait HfenceEntries {
+    /// Read the HFENCE entry at `index`, returning [`None`] if `index` is not
+    /// less than [`NUM_HFENCE_ENTRIES`]
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SynchronizeHfence`].
+    unsafe fn read_entry(self, index: usize) -> Option<HfenceEntry>;
+
+    /// Write the HFENCE entry at `index`, returning `false` without writing
+    /// anything if `index` is not less than [`NUM_HFENCE_ENTRIES`]. The
+    /// control word is written last so that the SBI implementation never
+    /// observes a pending entry with a partially written page range.
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SynchronizeHfence`].
+    unsafe fn write_entry(self, index: usize, entry: HfenceEntry) -> bool;
+}
+
+impl HfenceEntries for *mut SynchronizeHfence {
+    unsafe fn read_entry(self, index: usize) -> Option<HfenceEntry> {
+        if index >= NUM_HFENCE_ENTRIES {
+            return None;
+        }
+
+        let words = unsafe { core::ptr::addr_of_mut!((*self).0[index]) }.cast::<Volatile<usize>>();
+        unsafe {
+            Some(HfenceEntry::from_raw(
+                words.volatile_read(),
+                words.add(1).volatile_read(),
+                words.add(3).volatile_read(),
+            ))
+        }
+    }
+
+    unsafe fn write_entry(self, index: usize, entry: HfenceEntry) -> bool {
+        if index >= NUM_HFENCE_ENTRIES {
+            return false;
+        }
+
+        let words = unsafe { core::ptr::addr_of_mut!((*self).0[index]) }.cast::<Volatile<usize>>();
         unsafe {
-            core::ptr::addr_of_mut!((*self).scratch_space)
-                .offset(0x0800)
-                .cast::<SynchronizeHfence>()
+            words.add(1).volatile_write(entry.page_number);
+            words.add(3).volatile_write(entry.page_count);
+            words.volatile_write(entry.config());
         }
+
+        true
     }
 }
 
 const NUM_SRET_ENTRIES: usize = 512 / core::mem::size_of::<usize>();
 
+/// The synchronize SRET feature, consisting of the guest register state which
+/// is restored by the SBI implementation when emulating `SRET`
 #[repr(transparent)]
 pub struct SynchronizeSret([usize; NUM_SRET_ENTRIES]);
 
 const _: () = assert!(core::mem::size_of::<SynchronizeSret>() == 512);
 
+const SYNCHRONIZE_SRET_OFFSET: usize = 0x0000;
+
+const _: () = assert!(
+    SYNCHRONIZE_SRET_OFFSET + core::mem::size_of::<SynchronizeSret>() <= SCRATCH_SPACE_SIZE
+);
+
 impl sealed::Sealed for SynchronizeSret {}
 impl NaclFeature for SynchronizeSret {
     const ID: u32 = 0x00000002;
 }
 
+/// Access to the [`SynchronizeSret`] feature region of the shared memory
 pub trait SynchronizeSretFeature {
+    /// Returns a pointer to the [`SynchronizeSret`] feature region
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
     unsafe fn synchronize_sret(self) -> *mut SynchronizeSret;
 }
 
 impl SynchronizeSretFeature for *mut SharedMemoryLayout {
     unsafe fn synchronize_sret(self) -> *mut SynchronizeSret {
+        unsafe { scratch_space_region(self, SYNCHRONIZE_SRET_OFFSET) }
+    }
+}
+
+/// A general purpose register, excluding the hardwired zero register `x0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Gpr {
+    /// `x1` (`ra`)
+    Ra = 1,
+    /// `x2` (`sp`)
+    Sp = 2,
+    /// `x3` (`gp`)
+    Gp = 3,
+    /// `x4` (`tp`)
+    Tp = 4,
+    /// `x5` (`t0`)
+    T0 = 5,
+    /// `x6` (`t1`)
+    T1 = 6,
+    /// `x7` (`t2`)
+    T2 = 7,
+    /// `x8` (`s0`)
+    S0 = 8,
+    /// `x9` (`s1`)
+    S1 = 9,
+    /// `x10` (`a0`)
+    A0 = 10,
+    /// `x11` (`a1`)
+    A1 = 11,
+    /// `x12` (`a2`)
+    A2 = 12,
+    /// `x13` (`a3`)
+    A3 = 13,
+    /// `x14` (`a4`)
+    A4 = 14,
+    /// `x15` (`a5`)
+    A5 = 15,
+    /// `x16` (`a6`)
+    A6 = 16,
+    /// `x17` (`a7`)
+    A7 = 17,
+    /// `x18` (`s2`)
+    S2 = 18,
+    /// `x19` (`s3`)
+    S3 = 19,
+    /// `x20` (`s4`)
+    S4 = 20,
+    /// `x21` (`s5`)
+    S5 = 21,
+    /// `x22` (`s6`)
+    S6 = 22,
+    /// `x23` (`s7`)
+    S7 = 23,
+    /// `x24` (`s8`)
+    S8 = 24,
+    /// `x25` (`s9`)
+    S9 = 25,
+    /// `x26` (`s10`)
+    S10 = 26,
+    /// `x27` (`s11`)
+    S11 = 27,
+    /// `x28` (`t3`)
+    T3 = 28,
+    /// `x29` (`t4`)
+    T4 = 29,
+    /// `x30` (`t5`)
+    T5 = 30,
+    /// `x31` (`t6`)
+    T6 = 31,
+}
+
+impl Gpr {
+    /// Create a [`Gpr`] from the register number, returning [`None`] if
+    /// `index` is `0` or greater than `31`
+    pub const fn from_index(index: usize) -> Option<Self> {
+        match index {
+            1 => Some(Self::Ra),
+            2 => Some(Self::Sp),
+            3 => Some(Self::Gp),
+            4 => Some(Self::Tp),
+            5 => Some(Self::T0),
+            6 => Some(Self::T1),
+            7 => Some(Self::T2),
+            8 => Some(Self::S0),
+            9 => Some(Self::S1),
+            10 => Some(Self::A0),
+            11 => Some(Self::A1),
+            12 => Some(Self::A2),
+            13 => Some(Self::A3),
+            14 => Some(Self::A4),
+            15 => Some(Self::A5),
+            16 => Some(Self::A6),
+            17 => Some(Self::A7),
+            18 => Some(Self::S2),
+            19 => Some(Self::S3),
+            20 => Some(Self::S4),
+            21 => Some(Self::S5),
+            22 => Some(Self::S6),
+            23 => Some(Self::S7),
+            24 => Some(Self::S8),
+            25 => Some(Self::S9),
+            26 => Some(Self::S10),
+            27 => Some(Self::S11),
+            28 => Some(Self::T3),
+            29 => Some(Self::T4),
+            30 => Some(Self::T5),
+            31 => Some(Self::T6),
+            _ => None,
+        }
+    }
+
+    /// The register number
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// Every general purpose register must have a slot in the SRET scratch area
+const _: () = assert!(Gpr::T6.index() < NUM_SRET_ENTRIES);
+
+/// Access to the guest general purpose registers in the [`SynchronizeSret`]
+/// feature region, which are restored by the SBI implementation when emulating
+/// `SRET`. Register `xN` is stored in slot `N` of the region.
+pub trait SretScratch {
+    /// Read the value of the given general purpose register
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SynchronizeSret`].
+    unsafe fn read_gpr(self, gpr: Gpr) -> usize;
+
+    /// Write the value of the given general purpose register
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SynchronizeSret`].
+    unsafe fn write_gpr(self, gpr: Gpr, value: usize);
+}
+
+impl SretScratch for *mut SynchronizeSret {
+    unsafe fn read_gpr(self, gpr: Gpr) -> usize {
+        unsafe {
+            core::ptr::addr_of_mut!((*self).0[gpr.index()])
+                .cast::<Volatile<usize>>()
+                .volatile_read()
+        }
+    }
+
+    unsafe fn write_gpr(self, gpr: Gpr, value: usize) {
         unsafe {
-            core::ptr::addr_of_mut!((*self).scratch_space)
-                .offset(0x0000)
-                .cast::<SynchronizeSret>()
+            core::ptr::addr_of_mut!((*self).0[gpr.index()])
+                .cast::<Volatile<usize>>()
+                .volatile_write(value)
         }
     }
 }
 
 const NUM_AUTOSWAP_RESERVED_ENTRIES: usize = 128 / core::mem::size_of::<usize>() - 2;
 
+/// Flags selecting which CSRs are swapped by the SBI implementation when
+/// emulating `SRET`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct AutoswapFlags(usize);
 
+impl AutoswapFlags {
+    /// No CSRs are swapped
+    pub const NONE: Self = Self(0);
+    /// Swap the `hstatus` CSR with the value in the [`AutoswapCsr`] feature
+    /// region when emulating `SRET`
+    pub const AUTOSWAP_CSR: Self = Self(1 << 0);
+
+    /// Whether all of the flags set in `other` are also set in `self`
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for AutoswapFlags {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for AutoswapFlags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl Default for AutoswapFlags {
+    #[inline]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// The autoswap CSR feature, consisting of the CSR values swapped by the SBI
+/// implementation when emulating `SRET`
 #[repr(C)]
 pub struct AutoswapCsr {
     autoswap_flags: AutoswapFlags,
@@ -364,30 +1057,40 @@ pub struct AutoswapCsr {
 
 const _: () = assert!(core::mem::size_of::<AutoswapCsr>() == 128);
 
+const AUTOSWAP_CSR_OFFSET: usize = 0x0200;
+
+const _: () =
+    assert!(AUTOSWAP_CSR_OFFSET + core::mem::size_of::<AutoswapCsr>() <= SCRATCH_SPACE_SIZE);
+
 impl sealed::Sealed for AutoswapCsr {}
 impl NaclFeature for AutoswapCsr {
     const ID: u32 = 0x00000003;
 }
 
+/// A token representing the availability of the [`AutoswapCsr`] feature
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct AutoswapCsrToken(());
 
+/// Access to the [`AutoswapCsr`] feature region of the shared memory
 pub trait AutoswapCsrFeature {
+    /// Returns a pointer to the [`AutoswapCsr`] feature region
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a valid [`SharedMemoryLayout`].
     unsafe fn autoswap_csr(self) -> *mut AutoswapCsr;
 }
 
 impl AutoswapCsrFeature for *mut SharedMemoryLayout {
     unsafe fn autoswap_csr(self) -> *mut AutoswapCsr {
-        unsafe {
-            core::ptr::addr_of_mut!((*self).scratch_space)
-                .offset(0x0200)
-                .cast::<AutoswapCsr>()
-        }
+        unsafe { scratch_space_region(self, AUTOSWAP_CSR_OFFSET) }
     }
 }
 
+/// Probe whether the nested acceleration feature `F` is available
 #[doc(alias = "sbi_nacl_probe_feature")]
+#[must_use = "probing a feature has no effect other than returning whether it is available"]
 pub fn probe_feature<F: NaclFeature>() -> Result<bool, SbiError> {
     let value = unsafe { ecall1(F::ID as usize, EXTENSION_ID, 0) }?;
 
@@ -397,27 +1100,210 @@ pub fn probe_feature<F: NaclFeature>() -> Result<bool, SbiError> {
     }
 }
 
+/// The availability of each of the standard nested acceleration features, as
+/// returned by [`probe_all_features`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NaclFeatureSet {
+    /// Whether the [`SynchronizeCsr`] feature is available
+    pub synchronize_csr: bool,
+    /// Whether the [`SynchronizeHfence`] feature is available
+    pub synchronize_hfence: bool,
+    /// Whether the [`SynchronizeSret`] feature is available
+    pub synchronize_sret: bool,
+    /// Whether the [`AutoswapCsr`] feature is available
+    pub autoswap_csr: bool,
+}
+
+/// Probe the availability of all of the standard nested acceleration features
+/// using [`probe_feature`]
+#[must_use = "probing a feature has no effect other than returning whether it is available"]
+pub fn probe_all_features() -> Result<NaclFeatureSet, SbiError> {
+    Ok(NaclFeatureSet {
+        synchronize_csr: probe_feature::<SynchronizeCsr>()?,
+        synchronize_hfence: probe_feature::<SynchronizeHfence>()?,
+        synchronize_sret: probe_feature::<SynchronizeSret>()?,
+        autoswap_csr: probe_feature::<AutoswapCsr>()?,
+    })
+}
+
+/// Flags for setting the nested acceleration shared memory
+///
+/// The SBI specification currently reserves every bit of this parameter and
+/// requires it to be zero, so there are no named flags beyond [`Flags::NONE`].
+/// The usual flag operations are provided so that flags defined by future
+/// versions of the specification can be combined the same way as the other
+/// flag types in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct Flags(usize);
 
 impl Flags {
+    /// No flags
     pub const NONE: Self = Self(0);
+
+    /// The raw value of the flags
+    #[inline]
+    pub const fn bits(self) -> usize {
+        self.0
+    }
+
+    /// Whether all of the flags set in `other` are also set in `self`
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for Flags {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for Flags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
 }
 
+impl Default for Flags {
+    #[inline]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Set the physical address of the nested acceleration shared memory for the
+/// calling hart. `lo` contains the lower XLEN bits of the physical address and
+/// `hi` contains the upper XLEN bits, which must be zero on RV64. Until the
+/// shared memory has been set, calls which rely on it, such as
+/// [`synchronize_csr`], fail with [`SbiError::SHARED_MEMORY_UNAVAILABLE`].
+///
+/// ### Safety
+///
+/// The shared memory must be valid for reads and writes by the SBI
+/// implementation for as long as it is set, and must only be accessed through
+/// [`VolatileOps`] while it is set.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: `flags` is not valid.
+///
+/// [`SbiError::INVALID_ADDRESS`]: The shared memory address is not valid or is
+///     not 4096-byte aligned.
 #[doc(alias = "sbi_nacl_set_shmem")]
+#[must_use = "if setting the shared memory fails, nested acceleration features can't be used"]
 pub unsafe fn set_shared_memory(
     lo: PhysicalAddress<SharedMemoryLayout>,
     hi: PhysicalAddress<SharedMemoryLayout>,
     flags: Flags,
 ) -> Result<(), SbiError> {
-    unsafe { ecall3(lo.0, hi.0, flags.0, EXTENSION_ID, 1) }.map(drop)
+    unsafe { ecall3(lo.0 as usize, hi.0 as usize, flags.0, EXTENSION_ID, 1) }.map(drop)
+}
+
+/// Disable the nested acceleration shared memory for the calling hart by
+/// setting its physical address to all-ones (`usize::MAX`). After disabling,
+/// the SBI implementation will no longer access the previously set shared
+/// memory.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: `flags` is not valid.
+#[inline]
+#[must_use = "if disabling the shared memory fails, the SBI implementation may still access it"]
+pub fn disable_shared_memory(flags: Flags) -> Result<(), SbiError> {
+    unsafe { ecall3(usize::MAX, usize::MAX, flags.0, EXTENSION_ID, 1) }.map(drop)
 }
 
+/// Synchronize the given CSR, or all CSRs if [`UPDATE_ALL_CSRS`] is given,
+/// between the shared memory CSR space and the hardware CSRs.
+///
+/// ### Safety
+///
+/// The nested acceleration shared memory must have been set for the calling
+/// hart with [`set_shared_memory`], since the SBI implementation will read and
+/// write the shared memory CSR space and the [`SynchronizeCsr`] bitmap.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The [`SynchronizeCsr`] feature is not
+///     available.
+///
+/// [`SbiError::INVALID_PARAMETER`]: The CSR address is not valid.
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory has not been
+///     set.
+#[doc(alias = "sbi_nacl_sync_csr")]
+#[must_use = "a failed synchronization leaves the CSRs out of sync with the shared memory"]
 pub unsafe fn synchronize_csr<U: Into<UpdateCsrAddress>>(address: U) -> Result<(), SbiError> {
-    let UpdateCsrAddress(addr) = address.into();
-    unsafe { ecall1(addr as usize, EXTENSION_ID, 2) }.map(drop)
+    let csr_num = match address.into() {
+        // All ones selects every CSR
+        UPDATE_ALL_CSRS => usize::MAX,
+        UpdateCsrAddress(addr) => usize::from(addr),
+    };
+
+    unsafe { ecall1(csr_num, EXTENSION_ID, 2) }.map(drop)
+}
+
+/// Process the pending HFENCE request at `entry_index` in the
+/// [`SynchronizeHfence`] feature region, or all pending HFENCE requests if
+/// [`UPDATE_ALL_HFENCE_ENTRIES`] is given.
+///
+/// ### Safety
+///
+/// The nested acceleration shared memory must have been set for the calling
+/// hart with [`set_shared_memory`], and the [`SynchronizeHfence`] feature must
+/// be available (see [`probe_feature`]), since the SBI implementation will read
+/// and write the HFENCE entries in the shared memory.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The [`SynchronizeHfence`] feature is not
+///     available.
+///
+/// [`SbiError::INVALID_PARAMETER`]: `entry_index` is not a valid HFENCE entry
+///     index.
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory has not been
+///     set.
+#[doc(alias = "sbi_nacl_sync_hfence")]
+#[must_use = "a failed synchronization leaves the HFENCE entries unprocessed"]
+pub unsafe fn synchronize_hfence(entry_index: usize) -> Result<(), SbiError> {
+    unsafe { ecall1(entry_index, EXTENSION_ID, 3) }.map(drop)
 }
 
-pub fn foo() {
-    unsafe { synchronize_csr(csrs::Hstatus::ADDRESS) };
+/// Synchronize the CSRs and GPRs in the nested acceleration shared memory and
+/// have the SBI implementation emulate the `SRET` instruction, using the
+/// [`SynchronizeSret`] and [`AutoswapCsr`] feature regions. Since a successful
+/// `SRET` emulation never returns, this function only returns when the request
+/// fails.
+///
+/// ### Safety
+///
+/// The nested acceleration shared memory must have been set for the calling
+/// hart with [`set_shared_memory`], and the [`SynchronizeSret`] feature must be
+/// available (see [`probe_feature`]). On success, execution continues in the
+/// state described by the shared memory rather than returning to the caller.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: The [`SynchronizeSret`] feature is not
+///     available.
+///
+/// [`SbiError::SHARED_MEMORY_UNAVAILABLE`]: The shared memory has not been
+///     set.
+///
+/// [`SbiError::FAILED`]: The SBI implementation incorrectly returned success
+///     without emulating `SRET`.
+#[doc(alias = "sbi_nacl_sync_sret")]
+pub unsafe fn synchronize_sret() -> Result<core::convert::Infallible, SbiError> {
+    match unsafe { ecall0(EXTENSION_ID, 4) } {
+        // A successful `SRET` emulation never returns, so treat a spurious
+        // success as a failure
+        Ok(_) => Err(SbiError::FAILED),
+        Err(e) => Err(e),
+    }
 }
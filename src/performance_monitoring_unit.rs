@@ -5,7 +5,8 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{ecall0, ecall1, ecall3, SbiError};
+use crate::{ecall0, ecall1, ecall3, PhysicalAddress, RestrictedRange, SbiError};
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// Performance Monitoring Unit extension ID
 pub const EXTENSION_ID: usize = 0x504D55;
@@ -24,16 +25,54 @@ pub fn num_counters() -> usize {
 /// [`SbiError::INVALID_PARAMETER`]: The given [`CounterIndex`] is not valid.
 #[inline]
 #[doc(alias = "counter_get_info", alias = "sbi_pmu_counter_get_info")]
+#[must_use = "querying counter info has no effect other than returning it"]
 pub fn counter_info(counter_idx: CounterIndex) -> Result<CounterInfo, SbiError> {
     let res = unsafe { ecall1(counter_idx.0, EXTENSION_ID, 1) }?;
-    Ok(match (res as isize).is_positive() {
-        // Hardware counter
-        true => CounterInfo::Hardware {
-            csr_number: res & 0xFFF,
-            width: (res >> 12) & 0b0011_1111,
-        },
-        // Firmware counter
-        false => CounterInfo::Firmware,
+    Ok(CounterInfo::from_raw(res))
+}
+
+/// The number of counters as returned by [`num_counters`], cached by
+/// [`counter_info_checked`]. `usize::MAX` indicates that the number of counters
+/// has not yet been retrieved.
+static NUM_COUNTERS: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Retreive the information associated with a given performance counter, first
+/// checking that the [`CounterIndex`] is less than the number of counters. The
+/// number of counters is retrieved with [`num_counters`] on the first call and
+/// cached for all subsequent calls, so out-of-range indices are rejected
+/// without performing an SBI call.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The given [`CounterIndex`] is out of range
+///     or otherwise not valid.
+#[must_use = "querying counter info has no effect other than returning it"]
+pub fn counter_info_checked(counter_idx: CounterIndex) -> Result<CounterInfo, SbiError> {
+    let num_counters = match NUM_COUNTERS.load(Ordering::Relaxed) {
+        usize::MAX => {
+            let num_counters = num_counters();
+            NUM_COUNTERS.store(num_counters, Ordering::Relaxed);
+            num_counters
+        }
+        num_counters => num_counters,
+    };
+
+    if counter_idx.0 >= num_counters {
+        return Err(SbiError::INVALID_PARAMETER);
+    }
+
+    counter_info(counter_idx)
+}
+
+/// Returns an iterator over every performance counter along with its
+/// associated information as returned by [`counter_info`]. The number of
+/// counters is retrieved with [`num_counters`] once when the iterator is
+/// created.
+#[inline]
+pub fn counters() -> impl Iterator<Item = (CounterIndex, Result<CounterInfo, SbiError>)> {
+    (0..num_counters()).map(|idx| {
+        let counter_idx = CounterIndex(idx);
+        (counter_idx, counter_info(counter_idx))
     })
 }
 
@@ -52,6 +91,7 @@ pub fn counter_info(counter_idx: CounterIndex) -> Result<CounterInfo, SbiError>
     alias = "counter_config_matching",
     alias = "sbi_pmu_counter_config_matching"
 )]
+#[must_use = "the returned counter index is needed to use the configured counter"]
 pub fn configure_matching_counters(
     counter_mask: CounterIndexMask,
     config_flags: CounterConfigurationFlags,
@@ -99,6 +139,7 @@ pub fn configure_matching_counters(
 ///     already been started.
 #[inline]
 #[doc(alias = "counter_start", alias = "sbi_pmu_counter_start")]
+#[must_use = "a failed PMU request can leave counters in an unexpected state"]
 pub fn start_counters(
     counter_mask: CounterIndexMask,
     start_flags: CounterStartFlags,
@@ -143,6 +184,7 @@ pub fn start_counters(
 ///     already been stopped.
 #[inline]
 #[doc(alias = "counter_stop", alias = "sbi_pmu_counter_stop")]
+#[must_use = "a failed PMU request can leave counters in an unexpected state"]
 pub fn stop_counters(
     counter_mask: CounterIndexMask,
     stop_flags: CounterStopFlags,
@@ -159,6 +201,54 @@ pub fn stop_counters(
     }
 }
 
+/// Stop the performance counters described by the given [`CounterIndexMask`]
+/// and reset their counter to event mappings, freeing the counters to be
+/// configured for a different event. Equivalent to calling [`stop_counters`]
+/// with [`CounterStopFlags::RESET`], and intended for when profiling with the
+/// counters has finished.
+///
+/// ### Possible errors
+///
+/// See [`stop_counters`].
+#[inline]
+#[must_use = "a failed PMU request can leave counters in an unexpected state"]
+pub fn stop_and_reset_counters(counter_mask: CounterIndexMask) -> Result<(), SbiError> {
+    stop_counters(counter_mask, CounterStopFlags::RESET)
+}
+
+/// Start every performance counter, as reported by [`num_counters`]. Since a
+/// single [`CounterIndexMask`] can only select `usize::BITS` counters, the
+/// counters are partitioned with [`CounterIndexMask::covering`] and
+/// [`start_counters`] is called once per mask, stopping at the first error.
+/// Counters in the masks before the one which failed have been started.
+///
+/// ### Possible errors
+///
+/// See [`start_counters`].
+#[must_use = "a failed PMU request can leave counters in an unexpected state"]
+pub fn start_all_counters(
+    start_flags: CounterStartFlags,
+    initial_value: u64,
+) -> Result<(), SbiError> {
+    CounterIndexMask::covering(num_counters())
+        .try_for_each(|counter_mask| start_counters(counter_mask, start_flags, initial_value))
+}
+
+/// Stop every performance counter, as reported by [`num_counters`]. Since a
+/// single [`CounterIndexMask`] can only select `usize::BITS` counters, the
+/// counters are partitioned with [`CounterIndexMask::covering`] and
+/// [`stop_counters`] is called once per mask, stopping at the first error.
+/// Counters in the masks before the one which failed have been stopped.
+///
+/// ### Possible errors
+///
+/// See [`stop_counters`].
+#[must_use = "a failed PMU request can leave counters in an unexpected state"]
+pub fn stop_all_counters(stop_flags: CounterStopFlags) -> Result<(), SbiError> {
+    CounterIndexMask::covering(num_counters())
+        .try_for_each(|counter_mask| stop_counters(counter_mask, stop_flags))
+}
+
 /// Read the current value of the specified [`CounterIndex`]. On RV32 this will
 /// return the lower 32-bits of the firmware counter.
 ///
@@ -167,6 +257,7 @@ pub fn stop_counters(
 /// [`SbiError::INVALID_PARAMETER`]: The specified counter is not valid
 #[inline]
 #[doc(alias = "counter_fw_read", alias = "sbi_pmu_counter_fw_read")]
+#[must_use = "reading a counter has no effect other than returning its value"]
 pub fn read_firmware_counter(counter_idx: CounterIndex) -> Result<usize, SbiError> {
     unsafe { ecall1(counter_idx.0, EXTENSION_ID, 5) }
 }
@@ -179,10 +270,38 @@ pub fn read_firmware_counter(counter_idx: CounterIndex) -> Result<usize, SbiErro
 /// [`SbiError::INVALID_PARAMETER`]: The specified counter is not valid.
 #[inline]
 #[doc(alias = "counter_fw_read_hi", alias = "sbi_pmu_counter_fw_read_hi")]
+#[must_use = "reading a counter has no effect other than returning its value"]
 pub fn read_firmware_counter_hi(counter_idx: CounterIndex) -> Result<usize, SbiError> {
     unsafe { ecall1(counter_idx.0, EXTENSION_ID, 6) }
 }
 
+/// Read the full 64-bit value of the specified [`CounterIndex`] firmware
+/// counter. On RV64 this is a single call to [`read_firmware_counter`], while
+/// on RV32 this combines the results of [`read_firmware_counter`] and
+/// [`read_firmware_counter_hi`], retrying if the low bits overflow into the
+/// high bits between the two reads.
+///
+/// ### Possible errors
+///
+/// [`SbiError::INVALID_PARAMETER`]: The specified counter is not valid.
+#[inline]
+#[doc(alias = "counter_fw_read", alias = "sbi_pmu_counter_fw_read")]
+#[must_use = "reading a counter has no effect other than returning its value"]
+pub fn read_firmware_counter_u64(counter_idx: CounterIndex) -> Result<u64, SbiError> {
+    #[cfg(target_arch = "riscv64")]
+    return read_firmware_counter(counter_idx).map(|value| value as u64);
+
+    #[cfg(target_arch = "riscv32")]
+    loop {
+        let hi = read_firmware_counter_hi(counter_idx)?;
+        let lo = read_firmware_counter(counter_idx)?;
+
+        if read_firmware_counter_hi(counter_idx)? == hi {
+            return Ok(((hi as u64) << 32) | lo as u64);
+        }
+    }
+}
+
 /// Set the shared memory region address for PMU snapshotting.
 ///
 /// ### Safety
@@ -196,12 +315,63 @@ pub fn read_firmware_counter_hi(counter_idx: CounterIndex) -> Result<usize, SbiE
 ///     parameters is not accessible to S-mode.
 #[inline]
 #[doc(alias = "snapshot_set_shmem", alias = "sbi_pmu_snapshot_set_shmem")]
+#[must_use = "if setting the snapshot shared memory fails, no snapshots will be written to it"]
 pub unsafe fn set_snapshot_shared_memory_region(
     shmem_phys_lo: usize,
     shmem_phys_hi: usize,
     flags: SnapshotFlags,
 ) -> Result<usize, SbiError> {
-    unsafe { ecall3(shmem_phys_lo, shmem_phys_hi, flags.0, EXTENSION_ID, 6) }
+    unsafe { ecall3(shmem_phys_lo, shmem_phys_hi, flags.0, EXTENSION_ID, 7) }
+}
+
+/// Set the shared memory region used for PMU counter snapshots on the calling
+/// hart. The region must be 4096-byte aligned, which is guaranteed by the
+/// alignment of [`SnapshotSharedMemory`]. Passing all-ones (`usize::MAX`) for
+/// both `lo` and `hi` disables the snapshot shared memory region.
+///
+/// ### Safety
+///
+/// This function allows having the SBI write to arbitrary physical memory, and
+/// thus can cause undefined behavior if used incorrectly.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: Counter snapshots are not supported by the SBI
+///     implementation.
+///
+/// [`SbiError::INVALID_PARAMETER`]: The `flags` parameter is not valid.
+///
+/// [`SbiError::INVALID_ADDRESS`]: The memory region described by the given
+///     parameters is not accessible to S-mode or is not properly aligned.
+///
+/// [`SbiError::FAILED`]: The request failed for unspecified or unknown
+///     reasons.
+#[inline]
+#[doc(alias = "snapshot_set_shmem", alias = "sbi_pmu_snapshot_set_shmem")]
+#[must_use = "if setting the snapshot shared memory fails, no snapshots will be written to it"]
+pub unsafe fn set_snapshot_shared_memory(
+    lo: PhysicalAddress<SnapshotSharedMemory>,
+    hi: PhysicalAddress<SnapshotSharedMemory>,
+    flags: SnapshotFlags,
+) -> Result<(), SbiError> {
+    unsafe { set_snapshot_shared_memory_region(lo.0 as usize, hi.0 as usize, flags) }.map(drop)
+}
+
+/// Disable the PMU snapshot shared memory region for the calling hart by
+/// setting its physical address to all-ones (`usize::MAX`). After disabling,
+/// the SBI implementation will no longer access the previously set shared
+/// memory region.
+///
+/// ### Possible errors
+///
+/// [`SbiError::NOT_SUPPORTED`]: Counter snapshots are not supported by the SBI
+///     implementation.
+///
+/// [`SbiError::INVALID_PARAMETER`]: The `flags` parameter is not valid.
+#[inline]
+#[must_use = "if disabling the snapshot shared memory fails, the SBI implementation may still write to it"]
+pub fn disable_snapshot_shared_memory(flags: SnapshotFlags) -> Result<(), SbiError> {
+    unsafe { set_snapshot_shared_memory_region(usize::MAX, usize::MAX, flags) }.map(drop)
 }
 
 /// A convenience function for [`set_snapshot_shared_memory_region`] that allows
@@ -221,6 +391,7 @@ pub unsafe fn set_snapshot_shared_memory_region(
 ///     parameters is not accessible to S-mode.
 #[inline]
 #[doc(alias = "snapshot_set_shmem", alias = "sbi_pmu_snapshot_set_shmem")]
+#[must_use = "if setting the snapshot shared memory fails, no snapshots will be written to it"]
 pub unsafe fn set_snapshot_shared_memory_region_ptr(
     shared_memory_ptr: *mut SnapshotSharedMemory,
     flags: SnapshotFlags,
@@ -241,7 +412,8 @@ impl SnapshotFlags {
 
 /// A struct describing the layout of a PMU snapshot shared memory region
 #[derive(Clone)]
-#[repr(C)]
+#[doc(alias = "PmuSnapshot")]
+#[repr(C, align(4096))]
 pub struct SnapshotSharedMemory {
     /// Bitmap of counters which have overflowed. This is valid only if the
     /// `Sscofpmf`` ISA extension is available. Otherwise, it must be zero.
@@ -278,7 +450,7 @@ impl core::fmt::Debug for SnapshotSharedMemory {
 }
 
 /// Counter configuration flags
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CounterConfigurationFlags(usize);
 
 impl CounterConfigurationFlags {
@@ -317,10 +489,22 @@ impl CounterConfigurationFlags {
     pub const S_MODE_INHIBIT: Self = Self::SET_SINH;
 
     /// Hints to the SBI implementation to inhibit event counting in M-mode
-    pub const SET_MINH: Self = Self(1 << 6);
+    pub const SET_MINH: Self = Self(1 << 7);
     /// More verbose name for [`Self::SET_MINH`]. Hints to the SBI
     /// implementation to inhibit event counting in M-mode.
     pub const M_MODE_INHIBIT: Self = Self::SET_MINH;
+
+    /// The raw flag bits, as passed to the SBI implementation
+    #[inline]
+    pub const fn bits(self) -> usize {
+        self.0
+    }
+
+    /// Whether all of the flags in `other` are set
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
 }
 
 impl core::ops::BitOr for CounterConfigurationFlags {
@@ -346,6 +530,7 @@ impl Default for CounterConfigurationFlags {
 }
 
 /// Counter start flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CounterStartFlags(usize);
 
 impl CounterStartFlags {
@@ -378,6 +563,7 @@ impl Default for CounterStartFlags {
 }
 
 /// Counter stop flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CounterStopFlags(usize);
 
 impl CounterStopFlags {
@@ -410,6 +596,7 @@ impl Default for CounterStopFlags {
 }
 
 /// A bitmask of counter indices to be acted upon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CounterIndexMask {
     base: usize,
     mask: usize,
@@ -455,10 +642,106 @@ impl CounterIndexMask {
 
         self
     }
+
+    /// Select the given counter index, returning `None` if `counter_idx` is out
+    /// of the range of available selectable counter indices instead of leaving
+    /// the [`CounterIndexMask`] unchanged.
+    #[inline]
+    #[must_use]
+    pub const fn try_with(mut self, counter_idx: CounterIndex) -> Option<Self> {
+        if counter_idx.0 >= self.base && counter_idx.0 - self.base < usize::BITS as usize {
+            self.mask |= 1 << (counter_idx.0 - self.base);
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// The base counter index of this [`CounterIndexMask`], as passed to the
+    /// SBI implementation in the `counter_idx_base` parameter
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+
+    /// The raw mask of selected counter indices relative to
+    /// [`CounterIndexMask::base`], as passed to the SBI implementation in the
+    /// `counter_idx_mask` parameter
+    #[inline]
+    pub const fn raw_mask(&self) -> usize {
+        self.mask
+    }
+
+    /// Whether the given counter index is selected
+    #[inline]
+    pub const fn contains(&self, counter_idx: CounterIndex) -> bool {
+        counter_idx.0 >= self.base
+            && counter_idx.0 - self.base < usize::BITS as usize
+            && self.mask & (1 << (counter_idx.0 - self.base)) != 0
+    }
+
+    /// The number of selected counter indices
+    #[inline]
+    pub const fn count(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Returns an iterator over the minimum number of [`CounterIndexMask`]s
+    /// which together select every counter index in `0..num_counters`, in
+    /// increasing order of their base
+    #[inline]
+    pub fn covering(num_counters: usize) -> impl Iterator<Item = CounterIndexMask> {
+        const BITS: usize = usize::BITS as usize;
+
+        (0..num_counters).step_by(BITS).map(move |base| Self {
+            base,
+            mask: match num_counters - base {
+                remaining if remaining >= BITS => usize::MAX,
+                remaining => (1 << remaining) - 1,
+            },
+        })
+    }
+
+    /// Returns an iterator over the selected counter indices
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = CounterIndex> {
+        let Self { base, mask } = *self;
+        (0..usize::BITS as usize)
+            .filter(move |bit| mask & (1 << bit) != 0)
+            .map(move |bit| CounterIndex(base + bit))
+    }
+}
+
+/// A convenience macro to help create a [`CounterIndexMask`] from either one or
+/// more counter indices or a base and a list of counter indices, mirroring
+/// [`hart_mask!`](crate::hart_mask).
+///
+/// Examples:
+///
+/// A single counter index: `counter_mask!(my_counter_idx);`
+///
+/// Multiple counter indices: `counter_mask!(0, 2, 4);`
+///
+/// An explicit base with a list of counter indices: `counter_mask!(base: 0, ids: 1, 3);`
+#[macro_export]
+macro_rules! counter_mask {
+    ($counter_idx1:expr $(, $($counter_idx:expr),+ $(,)?)?) => {{
+        let mut counter_mask = $crate::pmu::CounterIndexMask::from(
+            $crate::pmu::CounterIndex::new($counter_idx1),
+        );
+        $($(counter_mask = counter_mask.with($crate::pmu::CounterIndex::new($counter_idx));)+)?
+        counter_mask
+    }};
+    (base: $base:literal, ids: $($counter_idx:expr),* $(,)?) => {{
+        let mut counter_mask =
+            $crate::pmu::CounterIndexMask::new($crate::pmu::CounterIndex::new($base));
+        $(counter_mask = counter_mask.with($crate::pmu::CounterIndex::new($counter_idx));)*
+        counter_mask
+    }};
 }
 
 /// A logical index assigned to a specific performance counter
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct CounterIndex(usize);
 
@@ -472,6 +755,7 @@ impl CounterIndex {
 
 /// Information about a specific performance counter
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum CounterInfo {
     /// The counter is a hardware performance counter
     Hardware {
@@ -482,7 +766,198 @@ pub enum CounterInfo {
         width: usize,
     },
     /// The counter is a firmware provided performance counter
-    Firmware,
+    Firmware {
+        /// The counter width. Equal to one less than the number of the bits
+        /// used by the counter.
+        width: usize,
+    },
+}
+
+impl CounterInfo {
+    /// Decode the raw counter information value returned by the SBI
+    /// implementation. Bit `XLEN-1` selects between a hardware and firmware
+    /// counter, bits `[11:0]` contain the CSR number, and bits `[17:12]`
+    /// contain the counter width. All other bits are reserved and ignored.
+    #[inline]
+    pub const fn from_raw(raw: usize) -> Self {
+        let width = (raw >> 12) & 0b0011_1111;
+        match raw >> (usize::BITS - 1) {
+            0 => Self::Hardware {
+                csr_number: raw & 0xFFF,
+                width,
+            },
+            _ => Self::Firmware { width },
+        }
+    }
+
+    /// The number of bits used by the counter
+    #[inline]
+    pub const fn width_bits(&self) -> u32 {
+        match self {
+            Self::Hardware { width, .. } | Self::Firmware { width } => *width as u32 + 1,
+        }
+    }
+}
+
+/// A single performance counter configured to monitor an event, which can be
+/// started, read, and stopped without managing counter masks manually. When
+/// the [`CounterSession`] is dropped, the counter is stopped if it is still
+/// running and its counter to event mapping is reset, freeing the counter to
+/// be configured again.
+#[derive(Debug)]
+pub struct CounterSession {
+    counter_idx: CounterIndex,
+    info: CounterInfo,
+}
+
+impl CounterSession {
+    /// Configure a counter from the given [`CounterIndexMask`] matching the
+    /// given event using [`configure_matching_counters`], returning a
+    /// [`CounterSession`] for the configured counter.
+    ///
+    /// If [`CounterConfigurationFlags::AUTO_START`] is passed, the counter is
+    /// started immediately.
+    ///
+    /// ### Possible errors
+    ///
+    /// [`SbiError::INVALID_PARAMETER`]: One or more of the given counter
+    ///     indices was not valid.
+    ///
+    /// [`SbiError::NOT_SUPPORTED`]: None of the given counters can monitor the
+    ///     specified event.
+    pub fn new(
+        counter_mask: CounterIndexMask,
+        config_flags: CounterConfigurationFlags,
+        event_idx: EventIndex,
+        event_data: u64,
+    ) -> Result<Self, SbiError> {
+        let counter_idx =
+            configure_matching_counters(counter_mask, config_flags, event_idx, event_data)?;
+        let info = match counter_info(counter_idx) {
+            Ok(info) => info,
+            Err(e) => {
+                // Release the counter we just configured so it isn't leaked
+                let _ = stop_and_reset_counters(CounterIndexMask::from(counter_idx));
+                return Err(e);
+            }
+        };
+
+        Ok(Self { counter_idx, info })
+    }
+
+    /// The [`CounterIndex`] of the configured counter
+    #[inline]
+    pub fn counter_index(&self) -> CounterIndex {
+        self.counter_idx
+    }
+
+    /// The [`CounterInfo`] of the configured counter
+    #[inline]
+    pub fn counter_info(&self) -> CounterInfo {
+        self.info
+    }
+
+    /// Start the counter.
+    ///
+    /// ### Possible errors
+    ///
+    /// [`SbiError::ALREADY_STARTED`]: The counter has already been started.
+    pub fn start(&mut self) -> Result<(), SbiError> {
+        start_counters(
+            CounterIndexMask::from(self.counter_idx),
+            CounterStartFlags::NONE,
+            0,
+        )
+    }
+
+    /// Read the current value of the counter. Hardware counters are read
+    /// directly from their CSR, while firmware counters are read with
+    /// [`read_firmware_counter_u64`].
+    ///
+    /// ### Possible errors
+    ///
+    /// [`SbiError::NOT_SUPPORTED`]: The counter is a hardware counter whose CSR
+    ///     is not a user-level counter CSR.
+    ///
+    /// [`SbiError::INVALID_PARAMETER`]: The firmware counter is not valid.
+    pub fn read(&self) -> Result<u64, SbiError> {
+        match self.info {
+            CounterInfo::Hardware { csr_number, .. } => {
+                read_hardware_counter(csr_number).ok_or(SbiError::NOT_SUPPORTED)
+            }
+            CounterInfo::Firmware { .. } => read_firmware_counter_u64(self.counter_idx),
+        }
+    }
+
+    /// Stop the counter.
+    ///
+    /// ### Possible errors
+    ///
+    /// [`SbiError::ALREADY_STOPPED`]: The counter has already been stopped.
+    pub fn stop(&mut self) -> Result<(), SbiError> {
+        stop_counters(
+            CounterIndexMask::from(self.counter_idx),
+            CounterStopFlags::NONE,
+        )
+    }
+}
+
+impl Drop for CounterSession {
+    fn drop(&mut self) {
+        // Stopping with `RESET` releases the counter to event mapping even if
+        // the counter is already stopped, in which case the SBI implementation
+        // reports `ALREADY_STOPPED`, so the result is ignored
+        let _ = stop_and_reset_counters(CounterIndexMask::from(self.counter_idx));
+    }
+}
+
+/// Read the value of the hardware counter CSR with the given CSR number,
+/// returning `None` if the CSR is not one of the user-level counter CSRs.
+fn read_hardware_counter(csr_number: usize) -> Option<u64> {
+    macro_rules! read_csr {
+        ($($csr:literal),+ $(,)?) => {
+            match csr_number {
+                $($csr => {
+                    #[cfg(target_arch = "riscv64")]
+                    let value = {
+                        let value: usize;
+                        unsafe { core::arch::asm!("csrr {}, {csr}", out(reg) value, csr = const $csr) };
+                        value as u64
+                    };
+
+                    #[cfg(target_arch = "riscv32")]
+                    let value = loop {
+                        let (hi, lo, hi2): (usize, usize, usize);
+                        unsafe {
+                            core::arch::asm!(
+                                "csrr {hi}, {csrh}",
+                                "csrr {lo}, {csr}",
+                                "csrr {hi2}, {csrh}",
+                                hi = out(reg) hi,
+                                lo = out(reg) lo,
+                                hi2 = out(reg) hi2,
+                                csr = const $csr,
+                                csrh = const $csr + 0x80,
+                            )
+                        };
+
+                        if hi == hi2 {
+                            break ((hi as u64) << 32) | lo as u64;
+                        }
+                    };
+
+                    Some(value)
+                })+
+                _ => None,
+            }
+        };
+    }
+
+    read_csr!(
+        0xC00, 0xC01, 0xC02, 0xC03, 0xC04, 0xC05, 0xC06, 0xC07, 0xC08, 0xC09, 0xC0A, 0xC0B, 0xC0C,
+        0xC0D, 0xC0E, 0xC0F, 0xC10, 0xC11, 0xC12, 0xC13, 0xC14, 0xC15, 0xC16, 0xC17, 0xC18, 0xC19,
+        0xC1A, 0xC1B, 0xC1C, 0xC1D, 0xC1E, 0xC1F,
+    )
 }
 
 mod sealed {
@@ -512,6 +987,44 @@ impl EventIndex {
     pub fn from_raw(event_type: u8, event_code: u16) -> Self {
         Self(((usize::from(event_type) & 0b1111) << 16) | usize::from(event_code))
     }
+
+    /// Create a new [`EventIndex`] from a packed event index value, such as one
+    /// previously returned by [`EventIndex::bits`]. Only the low 20 bits (the
+    /// event type and code) are kept. See [`EventIndex::from_raw`] to create an
+    /// [`EventIndex`] from an unpacked event type and code.
+    #[inline]
+    pub const fn from_bits(bits: usize) -> Self {
+        Self(bits & 0xF_FFFF)
+    }
+
+    /// The packed event index value, as passed to the SBI implementation
+    #[inline]
+    pub const fn bits(self) -> usize {
+        self.0
+    }
+
+    /// The raw event type value, such as [`EventType::TYPE_VALUE`]
+    #[inline]
+    pub const fn event_type_value(&self) -> usize {
+        (self.0 >> 16) & 0b1111
+    }
+
+    /// The raw event code, such as the value returned by
+    /// [`EventCode::to_code`]
+    #[inline]
+    pub const fn event_code(&self) -> u16 {
+        self.0 as u16
+    }
+
+    /// Create a new [`EventIndex`] for a raw hardware event with the given raw
+    /// event selector value, returning the [`EventIndex`] along with the
+    /// `event_data` value which must be passed alongside it to
+    /// [`configure_matching_counters`].
+    #[inline]
+    pub fn raw(selector: u64) -> (Self, u64) {
+        let code = HardwareRawEventCode::new(selector);
+        (Self::new(HardwareRawEvent, code), code.event_data())
+    }
 }
 
 /// A type of performance monitoring event
@@ -537,7 +1050,9 @@ impl EventType for HardwareGeneralEvent {
     type EventCode = HardwareGeneralEventCode;
 }
 
-/// A general hardware performance monitoring event code
+/// A general hardware performance monitoring event code. Vendor defined
+/// hardware events are not general events, and must instead be configured as a
+/// [`HardwareRawEvent`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(missing_docs)]
 #[non_exhaustive]
@@ -640,6 +1155,43 @@ impl HardwareCacheEventCode {
     ) -> Self {
         Self(((id as u16) << 3) | ((op as u16) << 1) | (result as u16))
     }
+
+    /// Decode a raw hardware cache event code, such as one returned by
+    /// [`EventIndex::event_code`], returning `None` if the cache unit or
+    /// operation bits don't correspond to a known value
+    #[inline]
+    pub const fn from_code(code: u16) -> Option<Self> {
+        match (
+            HardwareCacheEventCodeId::from_u16(code >> 3),
+            HardwareCacheEventCodeOperationId::from_u16((code >> 1) & 0b11),
+        ) {
+            (Some(id), Some(op)) => Some(Self::new(
+                id,
+                op,
+                HardwareCacheEventCodeResultId::from_u16(code & 1),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The cache unit, operation, and result making up this event code
+    #[inline]
+    pub const fn parts(
+        self,
+    ) -> (
+        HardwareCacheEventCodeId,
+        HardwareCacheEventCodeOperationId,
+        HardwareCacheEventCodeResultId,
+    ) {
+        match (
+            HardwareCacheEventCodeId::from_u16(self.0 >> 3),
+            HardwareCacheEventCodeOperationId::from_u16((self.0 >> 1) & 0b11),
+        ) {
+            (Some(id), Some(op)) => (id, op, HardwareCacheEventCodeResultId::from_u16(self.0 & 1)),
+            // Every `HardwareCacheEventCode` is constructed from valid parts
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl sealed::Sealed for HardwareCacheEventCode {}
@@ -671,6 +1223,21 @@ pub enum HardwareCacheEventCodeId {
     NumaNode = 6,
 }
 
+impl HardwareCacheEventCodeId {
+    const fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(Self::Level1Data),
+            1 => Some(Self::Level1Instruction),
+            2 => Some(Self::LastLevel),
+            3 => Some(Self::DataTlb),
+            4 => Some(Self::InstructionTlb),
+            5 => Some(Self::BranchPredictorUnit),
+            6 => Some(Self::NumaNode),
+            _ => None,
+        }
+    }
+}
+
 /// The cache operation to monitor
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(missing_docs)]
@@ -681,6 +1248,17 @@ pub enum HardwareCacheEventCodeOperationId {
     Prefetch = 2,
 }
 
+impl HardwareCacheEventCodeOperationId {
+    const fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(Self::Read),
+            1 => Some(Self::Write),
+            2 => Some(Self::Prefetch),
+            _ => None,
+        }
+    }
+}
+
 /// The result of the caching operation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(missing_docs)]
@@ -690,6 +1268,15 @@ pub enum HardwareCacheEventCodeResultId {
     Miss = 1,
 }
 
+impl HardwareCacheEventCodeResultId {
+    const fn from_u16(value: u16) -> Self {
+        match value & 1 {
+            0 => Self::Access,
+            _ => Self::Miss,
+        }
+    }
+}
+
 /// A raw hardware performance monitoring event
 #[derive(Debug, Clone, Copy, Default)]
 pub struct HardwareRawEvent;
@@ -700,9 +1287,31 @@ impl EventType for HardwareRawEvent {
     type EventCode = HardwareRawEventCode;
 }
 
-/// A raw hardware performance monitoring event code
-#[derive(Debug, Clone, Copy, Default)]
-pub struct HardwareRawEventCode;
+/// A raw hardware performance monitoring event code, carrying the
+/// platform-specific raw event selector value. The event code portion of the
+/// [`EventIndex`] for raw events is always zero, and the raw event selector is
+/// instead passed in the `event_data` parameter of
+/// [`configure_matching_counters`], which can be retrieved with
+/// [`HardwareRawEventCode::event_data`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HardwareRawEventCode(u64);
+
+impl HardwareRawEventCode {
+    /// Create a new [`HardwareRawEventCode`] from the given raw event selector
+    /// value. Only the lower 48 bits of the selector are used by the SBI
+    /// implementation to program the `mhpmeventX` CSR.
+    #[inline]
+    pub const fn new(selector: u64) -> Self {
+        Self(selector)
+    }
+
+    /// The raw event selector value to be passed in the `event_data` parameter
+    /// of [`configure_matching_counters`]
+    #[inline]
+    pub const fn event_data(self) -> u64 {
+        self.0
+    }
+}
 
 impl sealed::Sealed for HardwareRawEventCode {}
 impl EventCode for HardwareRawEventCode {
@@ -726,37 +1335,67 @@ impl EventType for FirmwareEvent {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[allow(missing_docs)]
 #[non_exhaustive]
-#[repr(u16)]
 pub enum FirmwareEventCode {
-    MisalignedLoad = 0,
-    MisalignedStore = 1,
-    AccessLoad = 2,
-    AccessStore = 3,
-    IllegalInstruction = 4,
-    SetTimer = 5,
-    IpiSent = 6,
-    IpiReceived = 7,
-    FenceISent = 8,
-    FenceIReceived = 9,
-    SfenceVmaSent = 10,
-    SfenceVmaReceived = 11,
-    SfenceVmaAsidSent = 12,
-    SfenceVmaAsidReceived = 13,
-    HfenceGvmaSent = 14,
-    HfenceGvmaReceived = 15,
-    HfenceGvmaVmidSent = 16,
-    HfenceGvmaVmidReceived = 17,
-    HfenceVvmaSent = 18,
-    HfenceVvmaReceived = 19,
-    HfenceVvmaAsidSent = 20,
-    HfenceVvmaAsidReceived = 21,
-    Platform = 65535,
+    MisalignedLoad,
+    MisalignedStore,
+    AccessLoad,
+    AccessStore,
+    IllegalInstruction,
+    SetTimer,
+    IpiSent,
+    IpiReceived,
+    FenceISent,
+    FenceIReceived,
+    SfenceVmaSent,
+    SfenceVmaReceived,
+    SfenceVmaAsidSent,
+    SfenceVmaAsidReceived,
+    HfenceGvmaSent,
+    HfenceGvmaReceived,
+    HfenceGvmaVmidSent,
+    HfenceGvmaVmidReceived,
+    HfenceVvmaSent,
+    HfenceVvmaReceived,
+    HfenceVvmaAsidSent,
+    HfenceVvmaAsidReceived,
+    /// SBI implementation specific firmware event, in the range
+    /// `0x100..=0xFFFE`
+    SbiImplementationSpecific(RestrictedRange<0x100, 0xFFFE>),
+    /// Platform specific firmware event. The platform specific event is
+    /// selected by the `event_data` parameter of
+    /// [`configure_matching_counters`].
+    Platform,
 }
 
 impl sealed::Sealed for FirmwareEventCode {}
 impl EventCode for FirmwareEventCode {
     #[inline]
     fn to_code(self) -> u16 {
-        self as u16
+        match self {
+            Self::MisalignedLoad => 0,
+            Self::MisalignedStore => 1,
+            Self::AccessLoad => 2,
+            Self::AccessStore => 3,
+            Self::IllegalInstruction => 4,
+            Self::SetTimer => 5,
+            Self::IpiSent => 6,
+            Self::IpiReceived => 7,
+            Self::FenceISent => 8,
+            Self::FenceIReceived => 9,
+            Self::SfenceVmaSent => 10,
+            Self::SfenceVmaReceived => 11,
+            Self::SfenceVmaAsidSent => 12,
+            Self::SfenceVmaAsidReceived => 13,
+            Self::HfenceGvmaSent => 14,
+            Self::HfenceGvmaReceived => 15,
+            Self::HfenceGvmaVmidSent => 16,
+            Self::HfenceGvmaVmidReceived => 17,
+            Self::HfenceVvmaSent => 18,
+            Self::HfenceVvmaReceived => 19,
+            Self::HfenceVvmaAsidSent => 20,
+            Self::HfenceVvmaAsidReceived => 21,
+            Self::SbiImplementationSpecific(n) => n.0 as u16,
+            Self::Platform => 0xFFFF,
+        }
     }
 }
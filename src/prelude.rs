@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: MPL-2.0
+// SPDX-FileCopyrightText: 2024 repnop
+//
+// This Source Code Form is subject to the terms of the Mozilla Public License,
+// v. 2.0. If a copy of the MPL was not distributed with this file, You can
+// obtain one at https://mozilla.org/MPL/2.0/.
+
+pub use crate::{cbbc, hart_mask, hsm, pmu, HartMask, PhysicalAddress, RestrictedRange, SbiError};
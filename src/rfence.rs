@@ -10,9 +10,48 @@ use crate::{ecall2, ecall4, ecall5, HartMask, SbiError};
 /// The RFENCE extension ID
 pub const EXTENSION_ID: usize = 0x52464E43;
 
+/// An address space identifier (ASID)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Asid(usize);
+
+impl Asid {
+    /// Create a new [`Asid`]
+    #[inline]
+    pub const fn new(asid: usize) -> Self {
+        Self(asid)
+    }
+
+    /// The raw ASID value
+    #[inline]
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+/// A virtual machine identifier (VMID)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Vmid(usize);
+
+impl Vmid {
+    /// Create a new [`Vmid`]
+    #[inline]
+    pub const fn new(vmid: usize) -> Self {
+        Self(vmid)
+    }
+
+    /// The raw VMID value
+    #[inline]
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
 /// Instructs the given harts to execute a `FENCE.I` instruction.
 #[inline]
 #[doc(alias = "sbi_remote_fence_i")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_fence_i(hart_mask: HartMask) -> Result<(), SbiError> {
     unsafe { ecall2(hart_mask.mask, hart_mask.base, EXTENSION_ID, 0).map(drop) }
 }
@@ -22,6 +61,7 @@ pub fn remote_fence_i(hart_mask: HartMask) -> Result<(), SbiError> {
 /// for which an `SFENCE.VMA` will be executed.
 #[inline]
 #[doc(alias = "sbi_remote_sfence_vma")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_sfence_vma(
     hart_mask: HartMask,
     start_addr: usize,
@@ -44,13 +84,26 @@ pub fn remote_sfence_vma(
 /// by `start_addr` and `size`, only covering the provided ASID. `size` is the
 /// size in bytes of the memory region for which an `SFENCE.VMA` will be
 /// executed.
+///
+/// ```rust,ignore
+/// use sbi::{rfence::Asid, HartMask};
+///
+/// // Flush a single 4 KiB page mapped at `0x8000_0000` in ASID 7 on harts 0-3
+/// sbi::rfence::remote_sfence_vma_asid(
+///     HartMask::from_ids([0, 1, 2, 3]),
+///     0x8000_0000,
+///     4096,
+///     Asid::new(7),
+/// )?;
+/// ```
 #[inline]
 #[doc(alias = "sbi_remote_sfence_vma_asid")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_sfence_vma_asid(
     hart_mask: HartMask,
     start_addr: usize,
     size: usize,
-    asid: usize,
+    asid: Asid,
 ) -> Result<(), SbiError> {
     unsafe {
         ecall5(
@@ -58,7 +111,7 @@ pub fn remote_sfence_vma_asid(
             hart_mask.base,
             start_addr,
             size,
-            asid,
+            asid.0,
             EXTENSION_ID,
             2,
         )
@@ -77,11 +130,12 @@ pub fn remote_sfence_vma_asid(
 ///     target harts do not implement the hypervisor extension.
 #[inline]
 #[doc(alias = "sbi_remote_hfence_gvma_vmid")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_hfence_gvma_vmid(
     hart_mask: HartMask,
     start_addr: usize,
     size: usize,
-    vmid: usize,
+    vmid: Vmid,
 ) -> Result<(), SbiError> {
     unsafe {
         ecall5(
@@ -89,7 +143,7 @@ pub fn remote_hfence_gvma_vmid(
             hart_mask.base,
             start_addr,
             size,
-            vmid,
+            vmid.0,
             EXTENSION_ID,
             3,
         )
@@ -108,6 +162,7 @@ pub fn remote_hfence_gvma_vmid(
 ///     target harts do not implement the hypervisor extension.
 #[inline]
 #[doc(alias = "sbi_remote_hfence_gvma")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_hfence_gvma(
     hart_mask: HartMask,
     start_addr: usize,
@@ -138,11 +193,12 @@ pub fn remote_hfence_gvma(
 ///     target harts do not implement the hypervisor extension.
 #[inline]
 #[doc(alias = "sbi_remote_hfence_vvma_asid")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_hfence_vvma_asid(
     hart_mask: HartMask,
     start_addr: usize,
     size: usize,
-    asid: usize,
+    asid: Asid,
 ) -> Result<(), SbiError> {
     unsafe {
         ecall5(
@@ -150,7 +206,7 @@ pub fn remote_hfence_vvma_asid(
             hart_mask.base,
             start_addr,
             size,
-            asid,
+            asid.0,
             EXTENSION_ID,
             5,
         )
@@ -170,6 +226,7 @@ pub fn remote_hfence_vvma_asid(
 ///     target harts do not implement the hypervisor extension.
 #[inline]
 #[doc(alias = "sbi_remote_hfence_vvma")]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
 pub fn remote_hfence_vvma(
     hart_mask: HartMask,
     start_addr: usize,
@@ -187,3 +244,138 @@ pub fn remote_hfence_vvma(
         .map(drop)
     }
 }
+
+fn fence_all<I>(
+    hart_ids: I,
+    mut fence: impl FnMut(HartMask) -> Result<(), SbiError>,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    HartMask::windows(hart_ids)
+        .try_for_each(|hart_mask| fence(hart_mask).map_err(|e| (e, hart_mask)))
+}
+
+/// Same as [`remote_fence_i`], but for an arbitrarily large set of hart IDs.
+/// The hart IDs are partitioned into the minimum number of [`HartMask`]s
+/// using [`HartMask::windows`] and a fence is issued for each, stopping at the
+/// first error. The other `*_all` functions in this module behave the same way.
+///
+/// ### Errors
+///
+/// On failure, the error is returned along with the [`HartMask`] for which
+/// the call failed. None of the harts selected by the returned [`HartMask`],
+/// nor any of the given harts with a greater hart ID, have been fenced.
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_fence_i_all<I>(hart_ids: I) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, remote_fence_i)
+}
+
+/// Same as [`remote_sfence_vma`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_sfence_vma_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_sfence_vma(hart_mask, start_addr, size)
+    })
+}
+
+/// Same as [`remote_sfence_vma_asid`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_sfence_vma_asid_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+    asid: Asid,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_sfence_vma_asid(hart_mask, start_addr, size, asid)
+    })
+}
+
+/// Same as [`remote_hfence_gvma_vmid`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_hfence_gvma_vmid_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+    vmid: Vmid,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_hfence_gvma_vmid(hart_mask, start_addr, size, vmid)
+    })
+}
+
+/// Same as [`remote_hfence_gvma`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_hfence_gvma_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_hfence_gvma(hart_mask, start_addr, size)
+    })
+}
+
+/// Same as [`remote_hfence_vvma_asid`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_hfence_vvma_asid_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+    asid: Asid,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_hfence_vvma_asid(hart_mask, start_addr, size, asid)
+    })
+}
+
+/// Same as [`remote_hfence_vvma`] for an arbitrary set of hart IDs; see
+/// [`remote_fence_i_all`].
+#[inline]
+#[must_use = "a failed remote fence can leave stale state on the target harts"]
+pub fn remote_hfence_vvma_all<I>(
+    hart_ids: I,
+    start_addr: usize,
+    size: usize,
+) -> Result<(), (SbiError, HartMask)>
+where
+    I: Iterator<Item = usize> + Clone,
+{
+    fence_all(hart_ids, |hart_mask| {
+        remote_hfence_vvma(hart_mask, start_addr, size)
+    })
+}
@@ -5,13 +5,14 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{ecall2, RestrictedRange, SbiError};
+use crate::{ecall2, ReservedValue, RestrictedRange, SbiError};
 
 /// System reset extension ID
 pub const EXTENSION_ID: usize = 0x53525354;
 
 /// The type of reset to perform
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum ResetType {
     /// Shutdown the system
@@ -35,8 +36,52 @@ impl From<ResetType> for u32 {
     }
 }
 
+impl TryFrom<u32> for ResetType {
+    type Error = ReservedValue;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Shutdown),
+            1 => Ok(Self::ColdReboot),
+            2 => Ok(Self::WarmReboot),
+            _ => match RestrictedRange::try_new(value) {
+                Some(n) => Ok(Self::PlatformSpecific(n)),
+                None => Err(ReservedValue { value }),
+            },
+        }
+    }
+}
+
+impl core::fmt::Display for ResetType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Shutdown => write!(f, "shutdown"),
+            Self::ColdReboot => write!(f, "cold-reboot"),
+            Self::WarmReboot => write!(f, "warm-reboot"),
+            Self::PlatformSpecific(n) => write!(f, "{:#X}", n.0),
+        }
+    }
+}
+
+impl core::str::FromStr for ResetType {
+    type Err = ParseResetError;
+
+    /// Parse a [`ResetType`] from the format produced by its `Display`
+    /// implementation: `shutdown`, `cold-reboot`, `warm-reboot`, or a
+    /// hexadecimal platform specific value prefixed with `0x`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shutdown" => Ok(Self::Shutdown),
+            "cold-reboot" => Ok(Self::ColdReboot),
+            "warm-reboot" => Ok(Self::WarmReboot),
+            _ => Self::try_from(parse_hex(s)?).map_err(|_| ParseResetError(())),
+        }
+    }
+}
+
 /// The reason for performing the reset
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum ResetReason {
     /// No reason for reset
@@ -60,6 +105,69 @@ impl From<ResetReason> for u32 {
     }
 }
 
+impl TryFrom<u32> for ResetReason {
+    type Error = ReservedValue;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::NoReason),
+            1 => Ok(Self::SystemFailure),
+            _ => match (
+                RestrictedRange::try_new(value),
+                RestrictedRange::try_new(value),
+            ) {
+                (Some(n), _) => Ok(Self::SbiSpecific(n)),
+                (_, Some(n)) => Ok(Self::PlatformSpecific(n)),
+                (None, None) => Err(ReservedValue { value }),
+            },
+        }
+    }
+}
+
+impl core::fmt::Display for ResetReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoReason => write!(f, "no-reason"),
+            Self::SystemFailure => write!(f, "system-failure"),
+            Self::SbiSpecific(n) => write!(f, "{:#X}", n.0),
+            Self::PlatformSpecific(n) => write!(f, "{:#X}", n.0),
+        }
+    }
+}
+
+impl core::str::FromStr for ResetReason {
+    type Err = ParseResetError;
+
+    /// Parse a [`ResetReason`] from the format produced by its `Display`
+    /// implementation: `no-reason`, `system-failure`, or a hexadecimal SBI or
+    /// platform specific value prefixed with `0x`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "no-reason" => Ok(Self::NoReason),
+            "system-failure" => Ok(Self::SystemFailure),
+            _ => Self::try_from(parse_hex(s)?).map_err(|_| ParseResetError(())),
+        }
+    }
+}
+
+/// Error returned when parsing a [`ResetType`] or [`ResetReason`] from a string
+/// fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseResetError(());
+
+impl core::fmt::Display for ParseResetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid reset type or reason")
+    }
+}
+
+fn parse_hex(s: &str) -> Result<u32, ParseResetError> {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or(ParseResetError(()))
+}
+
 /// Attempt to reset the system in the provided method, with a reason for the
 /// reset.
 ///
@@ -84,3 +192,45 @@ pub fn system_reset(
         Err(e) => Err(e),
     }
 }
+
+/// Shutdown the system. Equivalent to calling [`system_reset`] with
+/// [`ResetType::Shutdown`] and [`ResetReason::NoReason`].
+#[inline]
+pub fn shutdown() -> Result<core::convert::Infallible, SbiError> {
+    system_reset(ResetType::Shutdown, ResetReason::NoReason)
+}
+
+/// Shutdown the system in the most compatible way available. This first
+/// attempts [`shutdown`], and if the system reset extension is not supported,
+/// falls back to the legacy [`crate::legacy::shutdown`] call, which is
+/// available on older firmware that predates the system reset extension.
+///
+/// ### Panics
+///
+/// Panics if [`shutdown`] fails with any error other than
+/// [`SbiError::NOT_SUPPORTED`]. The SBI implementation supports the extension
+/// in that case, so the legacy call is not expected to succeed where it
+/// failed.
+#[inline]
+pub fn shutdown_or_legacy() -> ! {
+    match shutdown() {
+        Ok(never) => match never {},
+        Err(SbiError::NOT_SUPPORTED) => crate::legacy::shutdown(),
+        Err(e) => panic!("system reset shutdown failed: {e:?}"),
+    }
+}
+
+/// Power off all hardware and perform a cold boot. Equivalent to calling
+/// [`system_reset`] with [`ResetType::ColdReboot`] and
+/// [`ResetReason::NoReason`].
+#[inline]
+pub fn cold_reboot() -> Result<core::convert::Infallible, SbiError> {
+    system_reset(ResetType::ColdReboot, ResetReason::NoReason)
+}
+
+/// Reset processors and some hardware. Equivalent to calling [`system_reset`]
+/// with [`ResetType::WarmReboot`] and [`ResetReason::NoReason`].
+#[inline]
+pub fn warm_reboot() -> Result<core::convert::Infallible, SbiError> {
+    system_reset(ResetType::WarmReboot, ResetReason::NoReason)
+}
@@ -5,14 +5,15 @@
 // v. 2.0. If a copy of the MPL was not distributed with this file, You can
 // obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{ecall3, PhysicalAddress, RestrictedRange, SbiError};
+use crate::{ecall3, PhysicalAddress, ReservedValue, RestrictedRange, SbiError};
 use core::convert::Infallible;
 
 /// System suspend extension ID
 pub const EXTENSION_ID: usize = 0x53555350;
 
 /// A set of values describing possible sleep states to enter
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[non_exhaustive]
 pub enum SleepType {
     /// Sleep type similar to ACPI S2 or S3 modes. This mode requires all harts
@@ -34,6 +35,20 @@ impl From<SleepType> for u32 {
     }
 }
 
+impl TryFrom<u32> for SleepType {
+    type Error = ReservedValue;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::SuspendToRam),
+            _ => match RestrictedRange::try_new(value) {
+                Some(n) => Ok(Self::PlatformSpecific(n)),
+                None => Err(ReservedValue { value }),
+            },
+        }
+    }
+}
+
 /// Attempt to suspend the system in a way specified by the given [`SleepType`].
 /// After a successful suspension, the calling hart will be resumed in S-mode
 /// with `satp` and `sstatus.SIE` both initialized to `0` (thus, no memory
@@ -84,3 +99,38 @@ pub unsafe fn system_suspend(
         Err(e) => Err(e),
     }
 }
+
+/// Suspend the system to RAM with [`SleepType::SuspendToRam`], resuming
+/// execution in the `resume` function after a successful suspension. The
+/// `resume` function is called in S-mode with `satp` and `sstatus.SIE` both
+/// initialized to `0` (thus, no memory protection is enabled and interrupts
+/// are disabled), with the hart ID as its first argument and the `opaque`
+/// value as its second argument. All other harts must be in the
+/// [`HartState::Stopped`][0] state before calling this function.
+///
+/// [0]: crate::hart_state_management::HartState::Stopped
+///
+/// ### Safety
+///
+/// The address of `resume` must be its physical address, since it will be
+/// executed with no memory protection, and it must be properly set up to
+/// handle execution with an undefined register state (except `a0` and `a1`),
+/// such as having no valid stack pointer. See [`system_suspend`] for more
+/// information.
+///
+/// ### Possible errors
+///
+/// See [`system_suspend`].
+#[inline]
+pub unsafe fn suspend_to_ram(
+    resume: extern "C" fn(usize, usize) -> !,
+    opaque: usize,
+) -> Result<Infallible, SbiError> {
+    unsafe {
+        system_suspend(
+            SleepType::SuspendToRam,
+            PhysicalAddress::from_ptr(resume as *mut ()),
+            opaque,
+        )
+    }
+}
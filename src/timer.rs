@@ -10,6 +10,45 @@ use crate::SbiError;
 /// Timer extension ID
 pub const EXTENSION_ID: usize = 0x54494D45;
 
+/// A point in time, in ticks of the `time` CSR, at which a timer interrupt
+/// should be raised by [`set_timer`]. Constructing a [`Deadline`] requires
+/// choosing between an absolute time ([`Deadline::at`]) and an offset from the
+/// current time ([`Deadline::after`]), so that an offset can't be mistakenly
+/// passed as an absolute time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(u64);
+
+impl Deadline {
+    /// A deadline at the absolute value `time` of the `time` CSR
+    #[inline]
+    pub const fn at(time: u64) -> Self {
+        Self(time)
+    }
+
+    /// A deadline `ticks` in the future, relative to the current value of the
+    /// `time` CSR as returned by [`now`]
+    #[inline]
+    pub fn after(ticks: u64) -> Self {
+        Self(now().saturating_add(ticks))
+    }
+
+    /// The absolute value of the `time` CSR at which the deadline is reached
+    #[inline]
+    pub const fn absolute(self) -> u64 {
+        self.0
+    }
+}
+
+/// Schedule an interrupt for the given [`Deadline`]. To clear the timer
+/// interrupt without scheduling another timer event, use [`clear`] or mask the
+/// `STIE` bit of the `sie` CSR. This function will clear the pending timer
+/// interrupt bit. See [`now`] for how long each tick is.
+#[inline]
+#[must_use = "a failed timer request means the timer interrupt will not be raised"]
+pub fn set_timer(deadline: Deadline) -> Result<(), SbiError> {
+    set_timer_absolute(deadline.absolute())
+}
+
 /// Schedule an interrupt for `time` in the future. To clear the timer interrupt
 /// without scheduling another timer event, set a time infinitely far into the
 /// future (`u64::MAX`) or mask the `STIE` bit of the `sie` CSR. This function
@@ -17,13 +56,13 @@ pub const EXTENSION_ID: usize = 0x54494D45;
 /// 
 /// Note: `time` is an absolute time, not an offset from when the call is made.
 /// This means that if you want to set a time that is _n_ ticks in the future,
-/// you will need to read the `time` CSR first, then add the ticks to that. How
-/// you determine the number of time each tick represents is platform-dependent,
-/// and the frequency of the clock should be expressed in the
-/// `timebase-frequency` property of the CPU nodes in the devicetree, if you
-/// have one available.
+/// you will need to read the `time` CSR first, then add the ticks to that, or
+/// use [`set_timer`] with [`Deadline::after`]. See [`now`] for how long each
+/// tick is.
 #[rustfmt::skip]
-pub fn set_timer(time: u64) -> Result<(), SbiError> {
+#[doc(alias = "sbi_set_timer")]
+#[must_use = "a failed timer request means the timer interrupt will not be raised"]
+pub fn set_timer_absolute(time: u64) -> Result<(), SbiError> {
     #[cfg(target_arch = "riscv64")]
     unsafe { crate::ecall1(time as usize, EXTENSION_ID, 0).map(drop) }
 
@@ -33,3 +72,57 @@ pub fn set_timer(time: u64) -> Result<(), SbiError> {
     #[cfg(target_arch = "riscv32")]
     unsafe { crate::ecall2(time as usize, (time >> 32) as usize, EXTENSION_ID, 0).map(drop) }
 }
+
+/// Cancel any scheduled timer event and clear the pending timer interrupt bit
+/// (`sip.STIP`). This is equivalent to scheduling a timer event infinitely far
+/// into the future with `set_timer_absolute(u64::MAX)`.
+#[inline]
+#[must_use = "a failed clear means a previously set timer interrupt may still be raised"]
+pub fn clear() -> Result<(), SbiError> {
+    set_timer_absolute(u64::MAX)
+}
+
+/// Read the current value of the `time` CSR. How you determine the number of
+/// time each tick represents is platform-dependent, and the frequency of the
+/// clock should be expressed in the `timebase-frequency` property of the CPU
+/// nodes in the devicetree, if you have one available.
+#[inline]
+pub fn now() -> u64 {
+    #[cfg(target_arch = "riscv64")]
+    {
+        let time: u64;
+        unsafe { core::arch::asm!("csrr {}, time", out(reg) time, options(nomem, nostack)) };
+        time
+    }
+
+    // The high 32-bits may change between reading the two halves, so retry
+    // until a consistent value is read
+    #[cfg(target_arch = "riscv32")]
+    loop {
+        let (timeh, timel, timeh2): (u32, u32, u32);
+        unsafe {
+            core::arch::asm!(
+                "csrr {}, timeh",
+                "csrr {}, time",
+                "csrr {}, timeh",
+                out(reg) timeh,
+                out(reg) timel,
+                out(reg) timeh2,
+                options(nomem, nostack),
+            )
+        };
+
+        if timeh == timeh2 {
+            break (u64::from(timeh) << 32) | u64::from(timel);
+        }
+    }
+}
+
+/// Schedule an interrupt for `ticks` in the future, relative to the current
+/// value of the `time` CSR as returned by [`now`]. This function will clear the
+/// pending timer interrupt bit. See [`now`] for how long each tick is.
+#[inline]
+#[must_use = "a failed timer request means the timer interrupt will not be raised"]
+pub fn set_timer_after(ticks: u64) -> Result<(), SbiError> {
+    set_timer(Deadline::after(ticks))
+}
@@ -6,10 +6,53 @@ mod common;
 
 extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
     assert_eq!(sbi::base::impl_id(), sbi::base::SbiImplId::OpenSbi);
+    for (id, name) in [
+        (0, "Berkeley Boot Loader (BBL)"),
+        (1, "OpenSBI"),
+        (2, "Xvisor"),
+        (3, "KVM"),
+        (4, "RustSBI"),
+        (5, "Diosix"),
+        (6, "Coffer"),
+        (7, "Xen Project"),
+        (8, "PolarFire Hart Software Services"),
+        (9, "coreboot"),
+        (10, "oreboot"),
+        (0xFFFF, "unknown implementation (0xffff)"),
+    ] {
+        let impl_id = sbi::base::SbiImplId::from(id);
+        assert_eq!(usize::from(impl_id), id);
+        assert!(common::displays_as(impl_id, name));
+    }
+    assert_eq!(
+        sbi::base::SbiImplId::from(0xFFFF),
+        sbi::base::SbiImplId::Unknown(0xFFFF)
+    );
+    println!("🆗 implementation IDs successfully decoded");
     assert_eq!(
         sbi::base::spec_version(),
         sbi::base::SbiSpecVersion { major: 2, minor: 0 }
     );
+    let v1_0 = sbi::base::SbiSpecVersion { major: 1, minor: 0 };
+    let v1_11 = sbi::base::SbiSpecVersion {
+        major: 1,
+        minor: 11,
+    };
+    let v2_0 = sbi::base::SbiSpecVersion { major: 2, minor: 0 };
+    assert!(v1_0 < v1_11 && v1_11 < v2_0);
+    assert!(v2_0.at_least(1, 11) && v2_0.at_least(2, 0) && !v2_0.at_least(2, 1));
+    assert!(sbi::base::spec_version().at_least(2, 0));
+    println!("🆗 spec versions successfully compared");
+    for (raw, major, minor) in [
+        (0x0200_0000, 2, 0),
+        (0x0100_000B, 1, 11),
+        (0x8300_0001, 3, 1),
+    ] {
+        let version = sbi::base::SbiSpecVersion::from_raw(raw);
+        assert_eq!(version, sbi::base::SbiSpecVersion { major, minor });
+        assert_eq!(version.to_raw(), raw & 0x7FFF_FFFF);
+    }
+    println!("🆗 raw spec versions successfully decoded and encoded");
     assert_eq!(sbi::base::marchid(), 0);
     assert_eq!(sbi::base::mvendorid(), 0);
     assert_eq!(sbi::base::mimpid(), 0);
@@ -29,5 +72,60 @@ extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
     assert!(sbi::base::probe_extension(sbi::legacy::SET_TIMER_EID).is_available());
     assert!(sbi::base::probe_extension(sbi::legacy::SHUTDOWN_EID).is_available());
     println!("🆗 extensions successfully probed");
+    use sbi::extensions::{DebugConsole, Extension, HartStateManagement, Timer};
+    assert_eq!(Timer::ID, sbi::timer::EXTENSION_ID);
+    assert!(sbi::base::probe::<Timer>().is_available());
+    assert!(sbi::base::probe::<HartStateManagement>().is_available());
+    assert_eq!(
+        sbi::base::probe::<DebugConsole>().raw(),
+        sbi::base::probe_extension(sbi::debug_console::EXTENSION_ID).raw()
+    );
+    assert_eq!(
+        sbi::base::ExtensionId::StealTimeAccounting.into_usize(),
+        sbi::extensions::STEAL_TIME_ACCOUNTING_EXTENSION_ID
+    );
+    assert_eq!(sbi::base::ExtensionId::Timer.into_usize(), Timer::ID);
+    println!("🆗 extensions successfully probed by marker type");
+    assert_eq!(
+        sbi::base::probe_extension(sbi::timer::EXTENSION_ID).raw(),
+        1
+    );
+    assert_eq!(sbi::base::probe_extension(0x0BAD_CAFE).raw(), 0);
+    assert!(!sbi::base::probe_extension(0x0BAD_CAFE).is_available());
+    println!("🆗 raw probe values successfully retrieved");
+    let mut called = false;
+    assert_eq!(
+        sbi::base::with_extension(0x0BAD_CAFE, || {
+            called = true;
+            Ok(())
+        }),
+        Ok(None)
+    );
+    assert!(!called, "❌ closure called for an unavailable extension");
+    assert_eq!(
+        sbi::base::with_extension(sbi::timer::EXTENSION_ID, || Ok(5)),
+        Ok(Some(5))
+    );
+    assert_eq!(
+        sbi::base::with_extension(sbi::timer::EXTENSION_ID, || {
+            Err::<(), _>(sbi::SbiError::FAILED)
+        }),
+        Err(sbi::SbiError::FAILED)
+    );
+    println!("🆗 extensions successfully probed before use");
+    for (id, available) in sbi::base::available_extensions() {
+        match id {
+            sbi::base::ExtensionId::Base
+            | sbi::base::ExtensionId::Timer
+            | sbi::base::ExtensionId::HartStateManagement
+            | sbi::base::ExtensionId::DebugConsole => assert!(available),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        sbi::base::available_extensions().count(),
+        sbi::base::ExtensionId::ALL.len()
+    );
+    println!("🆗 available extensions successfully enumerated");
     common::exit(0);
 }
@@ -1,3 +1,12 @@
+// Shared harness for the QEMU integration tests. Every test is a bare-metal
+// S-mode kernel booted by OpenSBI (see `.cargo/config.toml`), so the tests only
+// build for the RISC-V targets and need nightly for the `naked_functions` and
+// `fn_align` features used by the entry points and trap handlers below. There
+// is no stable, host-side test suite: the crate itself only compiles for RISC-V
+// targets, and its pure logic (mask math, error decoding, event index packing)
+// is exercised by these tests rather than `#[cfg(test)]` modules. Checks that
+// code fails to compile live in `tests/ui` and are run by `tests/ui/run.sh`.
+
 use sbi::PhysicalAddress;
 
 static mut BOOT_HART_ID: usize = 0;
@@ -87,6 +96,35 @@ pub fn exit(status: u16) -> ! {
     unreachable!()
 }
 
+#[allow(dead_code)]
+pub fn displays_as(value: impl core::fmt::Display, expected: &str) -> bool {
+    use core::fmt::Write;
+
+    struct Buffer {
+        data: [u8; 128],
+        len: usize,
+    }
+
+    impl core::fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(core::fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut buffer = Buffer {
+        data: [0; 128],
+        len: 0,
+    };
+
+    write!(buffer, "{value}").is_ok() && &buffer.data[..buffer.len] == expected.as_bytes()
+}
+
 #[allow(dead_code)]
 pub fn wait(millis: u32) {
     let mut time = time();
@@ -0,0 +1,107 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::cbbc::{
+    registers::{HighestPerformance, PlatformSpecific},
+    CpccRegisterWidth, PerformanceLevel, PerformanceLimitedStatus, PerformanceRange, Register,
+    RegisterHandle,
+};
+use sbi::SbiError;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(CpccRegisterWidth::from(32), CpccRegisterWidth::Bits32);
+    assert_eq!(CpccRegisterWidth::from(64), CpccRegisterWidth::Bits64);
+    assert_eq!(CpccRegisterWidth::from(48), CpccRegisterWidth::Other(48));
+    assert_eq!(CpccRegisterWidth::Bits64.bits(), 64);
+    println!("🆗 register widths successfully decoded");
+
+    let range = PerformanceRange::new(PerformanceLevel(10), PerformanceLevel(100));
+    assert_eq!(range.clamp(PerformanceLevel(5)), PerformanceLevel(10));
+    assert_eq!(range.clamp(PerformanceLevel(150)), PerformanceLevel(100));
+    assert_eq!(range.clamp(PerformanceLevel(50)), PerformanceLevel(50));
+    assert!(range.contains(PerformanceLevel(100)) && !range.contains(PerformanceLevel(101)));
+    println!("🆗 performance levels successfully clamped");
+
+    for (raw, desired, minimum) in [
+        (0b00, false, false),
+        (0b01, true, false),
+        (0b10, false, true),
+        (0b11, true, true),
+        (0b100, false, false),
+    ] {
+        let status = PerformanceLimitedStatus::from_raw(raw);
+        assert_eq!(status.desired_excursion(), desired);
+        assert_eq!(status.minimum_excursion(), minimum);
+        assert_eq!(status.raw(), raw);
+    }
+    println!("🆗 performance limited status successfully decoded");
+
+    let available = sbi::base::probe_extension(sbi::cbbc::EXTENSION_ID).is_available();
+    match sbi::cbbc::probe_register_width(HighestPerformance) {
+        Ok(width) => println!("🆗 highest performance register width: {width:?}"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 CPPC not supported, skipping register probing")
+        }
+        Err(e) => panic!("❌ failed to probe register width: {e}"),
+    }
+
+    type SyntheticRegister = PlatformSpecific<0x8000_1234, u64>;
+    assert_eq!(SyntheticRegister::ID, 0x8000_1234);
+    let lowest = PlatformSpecific::<0x8000_0001, u32>::new();
+    assert_eq!(lowest, PlatformSpecific::default());
+    let highest = PlatformSpecific::<0xFFFF_FFFF, u64>::new();
+    assert_eq!(highest, PlatformSpecific::default());
+    println!("🆗 platform register IDs at the bounds of the platform range accepted");
+    match sbi::cbbc::probe_register(SyntheticRegister::new()) {
+        Ok(None) | Err(SbiError::INVALID_PARAMETER) => {
+            println!("🆗 synthetic platform register not implemented")
+        }
+        Ok(Some(width)) => println!("🆗 synthetic platform register width: {width}"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 CPPC not supported, skipping platform register probing")
+        }
+        Err(e) => panic!("❌ failed to probe platform register: {e}"),
+    }
+
+    match sbi::cbbc::probe_and_read(SyntheticRegister::new()) {
+        Ok(None) | Err(SbiError::INVALID_PARAMETER) => {
+            println!("🆗 synthetic platform register not read")
+        }
+        Ok(Some(value)) => println!("🆗 synthetic platform register value: {value}"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 CPPC not supported, skipping platform register read")
+        }
+        Err(e) => panic!("❌ failed to read platform register: {e}"),
+    }
+
+    match sbi::cbbc::probe_and_read(HighestPerformance) {
+        Ok(Some(value)) => {
+            assert_eq!(sbi::cbbc::read_register(HighestPerformance), Ok(value));
+            println!("🆗 highest performance register read: {value}");
+        }
+        Ok(None) => println!("🆗 highest performance register not implemented"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 CPPC not supported, skipping register read")
+        }
+        Err(e) => panic!("❌ failed to read register: {e}"),
+    }
+
+    match RegisterHandle::new(HighestPerformance) {
+        Ok(handle) => {
+            let expected = sbi::cbbc::read_register(HighestPerformance).expect("read_register");
+            for _ in 0..1000 {
+                assert_eq!(handle.read(), Ok(expected));
+            }
+            println!("🆗 register handle read 1000 times");
+        }
+        Err(SbiError::NOT_SUPPORTED) => {
+            println!("🆗 CPPC not supported, skipping register handle")
+        }
+        Err(e) => panic!("❌ failed to create register handle: {e}"),
+    }
+
+    common::exit(0);
+}
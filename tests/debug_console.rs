@@ -2,23 +2,24 @@
 #![no_std]
 #![no_main]
 
-use core::ptr::NonNull;
+use core::fmt::Write;
 
-use sbi::PhysicalAddress;
+use sbi::{debug_console::ConsoleExt, PhysicalAddress};
 
 mod common;
 
 static WRITE_OK: &str = "🆗 Successfully wrote to console with write_ptr";
+static WRITE_ALL_OK: &str = "\r\n🆗 Successfully wrote the entire buffer to console with write_all";
 static READ_MSG: &str = "\r\nReading test text from QEMU input file\r\n";
 
 extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
-    let mut buf = &mut [0u8; 256];
+    let buf = &mut [0u8; 256];
 
     let read =
         unsafe { sbi::debug_console::read_ptr(PhysicalAddress::from_ptr(buf)).expect("read ok") };
 
     assert_eq!(
-        core::str::from_utf8(&mut buf[..read]).unwrap(),
+        core::str::from_utf8(&buf[..read]).unwrap(),
         "This is some test UART input",
         "❌ didn't read any input"
     );
@@ -32,7 +33,95 @@ extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
         .expect("write ok");
     }
 
-    sbi::debug_console::write_byte(b'A').expect("write ok");
+    unsafe {
+        sbi::debug_console::write_all(PhysicalAddress::from_ptr(WRITE_ALL_OK.as_bytes()
+            as *const [u8]
+            as *mut [u8]))
+        .expect("write_all ok");
+    }
+
+    let mut calls = [(0, 0, 0); 4];
+    let mut num_calls = 0;
+    let written = sbi::debug_console::write_all_with(
+        PhysicalAddress::new(usize::MAX - 1),
+        PhysicalAddress::new(7),
+        10,
+        |lo, hi, num_bytes| {
+            calls[num_calls] = (lo.as_ptr() as usize, hi.as_ptr() as usize, num_bytes);
+            num_calls += 1;
+            Ok(if num_calls == 1 { 3 } else { num_bytes })
+        },
+    );
+    assert_eq!(written, Ok(()), "❌ short write not completed");
+    assert_eq!(
+        calls[..num_calls],
+        [(usize::MAX - 1, 7, 10), (1, 8, 7)],
+        "❌ address not advanced after a short write"
+    );
+    let failed = sbi::debug_console::write_all_with(
+        PhysicalAddress::new(0x1000),
+        PhysicalAddress::new(0),
+        10,
+        |_, _, _| Err(sbi::SbiError::FAILED),
+    );
+    assert_eq!(
+        failed,
+        Err(sbi::SbiError::FAILED),
+        "❌ write error not returned"
+    );
+    println!("\r\n🆗 Short writes successfully completed with write_all_with");
+
+    let identity = unsafe { sbi::debug_console::PhysicalIdentity::new() };
+    sbi::debug_console::write_str(
+        &identity,
+        "\r\n🆗 Successfully wrote to console with write_str",
+    )
+    .expect("write_str ok");
+
+    let wrapping = unsafe {
+        sbi::debug_console::write(
+            PhysicalAddress::new(usize::MAX - 3),
+            PhysicalAddress::new(usize::MAX),
+            8,
+        )
+    };
+    assert_eq!(
+        wrapping,
+        Err(sbi::SbiError::INVALID_PARAMETER),
+        "❌ wrapping memory region"
+    );
+
+    let mut console = unsafe { sbi::debug_console::best_available() };
+    assert!(matches!(
+        console,
+        sbi::debug_console::AvailableConsole::DebugConsole(_)
+    ));
+    console.put_byte(b'B').expect("put_byte ok");
+    console
+        .write_bytes("\r\n🆗 Successfully wrote to the best available console".as_bytes())
+        .expect("write_bytes ok");
+    sbi::legacy::LegacyConsole
+        .write_bytes("\r\n🆗 Successfully wrote to the legacy console\r\n".as_bytes())
+        .expect("write_bytes ok");
+
+    let written: Result<(), sbi::SbiError> = sbi::debug_console::write_byte(b'A');
+    assert_eq!(written, Ok(()), "❌ write_byte failed");
+
+    sbi::emergency_print("\r\n🆗 Successfully wrote to console with emergency_print");
+
+    let mut writer = unsafe { sbi::debug_console::Writer::new() };
+    writeln!(
+        writer,
+        "\r\n🆗 Successfully wrote to console with {}",
+        core::any::type_name::<sbi::debug_console::Writer>()
+    )
+    .expect("fmt write ok");
+
+    // 100 bytes spanning more than one bounce buffer chunk
+    let long = "\r\n🆗 Successfully wrote a long string to console with Writer, split into two bounce buffer chunks.";
+    assert_eq!(long.len(), 100);
+    writer.write_str(long).expect("fmt write ok");
+    writer.write_str("\r\n").expect("fmt write ok");
 
     common::exit(0);
 }
@@ -0,0 +1,31 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+use sbi::PhysicalAddress;
+
+mod common;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    println!("Echoing test text from QEMU input file");
+
+    let buf = &mut [0u8; 256];
+    let echoed = unsafe { sbi::debug_console::loopback_once(PhysicalAddress::from_ptr(buf)) }
+        .expect("loopback_once ok");
+    println!();
+
+    assert_eq!(
+        core::str::from_utf8(&buf[..echoed]).unwrap(),
+        "This is some test UART input",
+        "❌ didn't echo the test input"
+    );
+    println!("🆗 Successfully echoed test input");
+
+    let empty = &mut [0u8; 16];
+    let echoed = unsafe { sbi::debug_console::loopback_once(PhysicalAddress::from_ptr(empty)) }
+        .expect("loopback_once ok");
+    assert_eq!(echoed, 0, "❌ echoed input after it was consumed");
+    println!("🆗 Nothing echoed once input was consumed");
+
+    common::exit(0);
+}
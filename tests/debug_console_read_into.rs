@@ -0,0 +1,23 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let identity = unsafe { sbi::debug_console::PhysicalIdentity::new() };
+
+    let prefix = &mut [0u8; 4];
+    let filled = sbi::debug_console::read_into(&identity, prefix).expect("read_into ok");
+    assert_eq!(filled, b"This", "❌ didn't read input with read_into");
+
+    let rest = &mut [0u8; 256];
+    let filled = sbi::debug_console::read_into(&identity, rest).expect("read_into ok");
+    assert_eq!(
+        filled, b" is some test UART input",
+        "❌ didn't read the remaining input with read_into"
+    );
+    println!("🆗 Successfully read test input with read_into");
+
+    common::exit(0);
+}
@@ -0,0 +1,30 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::debug_triggers::SharedMemoryFlags;
+use sbi::SbiError;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let available = sbi::base::probe_extension(sbi::debug_triggers::EXTENSION_ID).is_available();
+
+    match sbi::debug_triggers::num_triggers(0) {
+        Ok(triggers) => println!("🆗 {triggers} debug triggers available"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 debug triggers not supported, skipping trigger count")
+        }
+        Err(e) => panic!("❌ failed to get the number of triggers: {e}"),
+    }
+
+    match sbi::debug_triggers::disable_shared_memory(SharedMemoryFlags::NONE) {
+        Ok(()) => println!("🆗 shared memory successfully disabled"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 debug triggers not supported, skipping")
+        }
+        Err(e) => panic!("❌ failed to disable shared memory: {e}"),
+    }
+
+    common::exit(0);
+}
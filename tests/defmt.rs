@@ -0,0 +1,24 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::SbiError;
+
+fn assert_format<T: defmt::Format>(_: &T) {}
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert_format(&SbiError::FAILED);
+    assert_format(&sbi::base::spec_version());
+    assert_format(&sbi::base::impl_id());
+    assert_format(&sbi::hsm::HartState::Started);
+    assert_format(&sbi::hsm::SuspendType::DefaultRetentive);
+    assert_format(&sbi::system_reset::ResetType::Shutdown);
+    assert_format(&sbi::system_reset::ResetReason::NoReason);
+    assert_format(&sbi::system_suspend::SleepType::SuspendToRam);
+    assert_format(&sbi::pmu::CounterInfo::Firmware { width: 63 });
+    println!("🆗 types successfully implement defmt::Format");
+
+    common::exit(0);
+}
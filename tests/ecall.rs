@@ -0,0 +1,53 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::SbiError;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let args = [usize::MAX, 0, 0];
+    let fixed = unsafe { sbi::ecall3(args[0], args[1], args[2], sbi::hsm::EXTENSION_ID, 0) };
+    let generic = unsafe { sbi::ecall(&args, sbi::hsm::EXTENSION_ID, 0) };
+    assert_eq!(fixed, Err(SbiError::INVALID_PARAMETER));
+    assert_eq!(generic, fixed);
+    println!("🆗 three argument ecall matches ecall3");
+
+    let probe = unsafe { sbi::ecall(&[sbi::timer::EXTENSION_ID], 0x10, 3) };
+    assert_eq!(probe, unsafe {
+        sbi::ecall1(sbi::timer::EXTENSION_ID, 0x10, 3)
+    });
+    assert_eq!(unsafe { sbi::ecall(&[], 0x10, 0) }, unsafe {
+        sbi::ecall0(0x10, 0)
+    });
+    println!("🆗 generic ecall matches fixed-arity ecalls");
+
+    let (version, _) = unsafe { sbi::ecall0_2(0x10, 0) }.expect("get_spec_version");
+    assert_eq!(Ok(version), unsafe { sbi::ecall0(0x10, 0) });
+    assert_eq!(
+        unsafe { sbi::ecall3_2(usize::MAX, 0, 0, sbi::hsm::EXTENSION_ID, 0) },
+        Err(SbiError::INVALID_PARAMETER)
+    );
+    println!("🆗 two-value ecalls match single-value ecalls");
+
+    let probe = [
+        sbi::timer::EXTENSION_ID,
+        0xDEAD,
+        0xDEAD,
+        0xDEAD,
+        0xDEAD,
+        0xDEAD,
+    ];
+    let (error, value) = unsafe { sbi::raw_ecall(probe, 1, 0x10, 3) };
+    assert_eq!(error, 0);
+    assert_eq!(Ok(value), unsafe {
+        sbi::ecall1(sbi::timer::EXTENSION_ID, 0x10, 3)
+    });
+    let (error, _) =
+        unsafe { sbi::raw_ecall([usize::MAX, 0, 0, 0, 0, 0], 3, sbi::hsm::EXTENSION_ID, 0) };
+    assert_eq!(SbiError::from_raw(error), SbiError::INVALID_PARAMETER);
+    println!("🆗 raw ecall matches fixed-arity ecalls");
+
+    common::exit(0);
+}
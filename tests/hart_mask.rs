@@ -0,0 +1,125 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::hash::{Hash, Hasher};
+use sbi::{HartMask, HartMaskOverflow};
+
+fn hash(value: impl Hash) -> u64 {
+    struct Fnv1a(u64);
+
+    impl Hasher for Fnv1a {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x100_0000_01B3);
+            }
+        }
+    }
+
+    let mut hasher = Fnv1a(0xCBF2_9CE4_8422_2325);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(
+        HartMask::from_ids([3, 4, 5, 6]),
+        sbi::hart_mask!(3, 4, 5, 6),
+        "❌ contiguous hart IDs"
+    );
+    assert_eq!(
+        HartMask::from_ids([20, 2, 31, 10]),
+        sbi::hart_mask!(2, 10, 20, 31),
+        "❌ sparse hart IDs"
+    );
+    assert_eq!(HartMask::from_ids([]), HartMask::new(0));
+    assert_eq!(
+        HartMask::try_from_ids([1, usize::BITS as usize + 1]),
+        Err(HartMaskOverflow {
+            hart_id: usize::BITS as usize + 1
+        }),
+        "❌ hart IDs too wide"
+    );
+    assert_eq!(
+        HartMask::try_from_ids([usize::BITS as usize, 0]),
+        Err(HartMaskOverflow { hart_id: 0 }),
+        "❌ hart IDs too wide"
+    );
+    println!("🆗 hart mask constructed from hart IDs");
+
+    let low_base = sbi::hart_mask!(base: 0, ids: 2);
+    let high_base = HartMask::from(2);
+    assert_eq!(low_base, high_base, "❌ same harts with different bases");
+    assert_eq!(hash(low_base), hash(high_base));
+    assert_eq!(HartMask::new(0), HartMask::new(5), "❌ empty hart masks");
+    assert_ne!(low_base, HartMask::from(3));
+    assert!(low_base.contains(2) && !low_base.contains(0) && !low_base.contains(3));
+    println!("🆗 hart masks compared by selected hart IDs");
+
+    let hart_mask = sbi::hart_mask!(3, 4, 6);
+    assert_eq!(hart_mask.base(), 3);
+    assert_eq!(hart_mask.raw_mask(), 0b1011);
+    let hart_mask = sbi::hart_mask!(base: 1, ids: 2, 3);
+    assert_eq!(hart_mask.base(), 1);
+    assert_eq!(hart_mask.raw_mask(), 0b110);
+    println!("🆗 hart mask base and raw mask retrieved");
+
+    let bits = usize::BITS as usize;
+    let hart_mask = sbi::hart_mask!(base: 8, ids: 10, 12);
+    let lower = hart_mask.rebased(4).expect("rebase down");
+    assert_eq!((lower.base(), lower.raw_mask()), (4, 0b1_0100_0000));
+    let higher = lower.rebased(10).expect("rebase up");
+    assert_eq!((higher.base(), higher.raw_mask()), (10, 0b101));
+    assert_eq!(lower, hart_mask);
+    assert_eq!(higher, hart_mask);
+    assert_eq!(
+        hart_mask.rebased(12 - (bits - 1)).map(|m| m.raw_mask()),
+        Some(0b101 << (bits - 3))
+    );
+    assert_eq!(hart_mask.rebased(11), None, "❌ hart ID below the new base");
+    assert_eq!(
+        hart_mask.rebased(12 - bits),
+        None,
+        "❌ hart ID too far above the new base"
+    );
+    let empty = HartMask::new(3).rebased(100).expect("rebase empty");
+    assert_eq!((empty.base(), empty.raw_mask()), (100, 0));
+    println!("🆗 hart masks rebased");
+
+    let online = sbi::hart_mask!(0, 1, 2, 3);
+    let idle = sbi::hart_mask!(base: 1, ids: 2, 3, 5);
+    assert_eq!(online | idle, Some(sbi::hart_mask!(0, 1, 2, 3, 5)));
+    assert_eq!(online.union(idle), online | idle);
+    assert_eq!(online & idle, sbi::hart_mask!(2, 3));
+    assert_eq!(online.intersection(idle), online & idle);
+    println!("🆗 overlapping hart masks combined");
+
+    let target = HartMask::from(10);
+    let combined = (online | target).expect("disjoint union");
+    assert_eq!(combined, sbi::hart_mask!(0, 1, 2, 3, 10));
+    assert_eq!(combined.base(), 0);
+    assert_eq!(online & target, HartMask::new(0));
+    assert_eq!(online | HartMask::new(7), Some(online));
+    assert_eq!(HartMask::new(7) | online, Some(online));
+    assert_eq!(online & HartMask::new(0), HartMask::new(0));
+    println!("🆗 disjoint hart masks combined");
+
+    let far = HartMask::from(bits);
+    assert_eq!(HartMask::from(0) | far, None, "❌ union too wide");
+    assert_eq!(far | HartMask::from(0), None, "❌ union too wide");
+    assert_eq!(HartMask::from(1) | far, Some(HartMask::from_ids([1, bits])));
+    assert_eq!(HartMask::from(0) & far, HartMask::new(0));
+    assert_eq!(
+        HartMask::from(0) & HartMask::from(usize::MAX),
+        HartMask::new(0)
+    );
+    println!("🆗 hart masks too wide to combine rejected");
+
+    common::exit(0);
+}
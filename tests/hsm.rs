@@ -11,6 +11,10 @@ static HART_BOOTED: AtomicBool = AtomicBool::new(false);
 
 #[no_mangle]
 extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(HartState::from(6), HartState::ResumePending);
+    assert_eq!(HartState::from(7), HartState::Unknown(7));
+    println!("🆗 Hart states successfully decoded");
+
     let target_hart = if hart_id == 0 { 1 } else { 0 };
     common::start_other_hart(other_main);
     common::wait(10);
@@ -24,6 +28,15 @@ extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
         HartState::Started,
     );
 
+    #[allow(deprecated)]
+    {
+        use sbi::hart_state_management::{hart_status, HartStatus};
+        assert_eq!(
+            hart_status(target_hart).expect("hart_status"),
+            HartStatus::Started,
+        );
+    }
+
     println!("🆗 Hart {target_hart} started");
 
     common::wait(150);
@@ -0,0 +1,64 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::hart_state_management::{
+    suspend_non_retentive, suspend_retentive, NonRetentiveSuspendType, RetentiveSuspendType,
+};
+use sbi::{timer::Deadline, PhysicalAddress};
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    // Only enable the supervisor timer interrupt in `sie` so that it wakes the
+    // hart without trapping
+    unsafe { core::arch::asm!("csrs sie, {}", in(reg) 1 << 5) };
+
+    let start = common::time();
+    sbi::timer::set_timer(Deadline::at(start + 1000)).expect("set_timer");
+    suspend_retentive(RetentiveSuspendType::Default).expect("suspend_retentive");
+    assert!(common::time() >= start + 1000, "❌ hart resumed early");
+    sbi::timer::clear().expect("clear");
+
+    println!("🆗 Hart resumed from retentive suspend");
+
+    let start = common::time();
+    sbi::timer::set_timer(Deadline::at(start + 1000)).expect("set_timer");
+    let error = unsafe {
+        suspend_non_retentive(NonRetentiveSuspendType::Default {
+            resume_address: PhysicalAddress::from_ptr(resume_entry as *mut ()),
+            opaque: start as usize,
+        })
+    };
+
+    panic!("❌ non-retentive suspend failed: {error:?}");
+}
+
+#[naked]
+#[rustfmt::skip]
+unsafe extern "C" fn resume_entry(hart_id: usize, opaque: usize) -> ! {
+    core::arch::asm!(
+        "
+            .option push
+            .option norelax
+            lla gp, __global_pointer$
+            .option pop
+
+            lla sp, __stack_start
+            j {resumed}
+        ",
+        resumed = sym resumed,
+        options(noreturn),
+    )
+}
+
+extern "C" fn resumed(_hart_id: usize, start: usize) -> ! {
+    assert!(
+        common::time() >= start as u64 + 1000,
+        "❌ hart resumed early"
+    );
+    sbi::timer::clear().expect("clear");
+
+    println!("🆗 Hart resumed from non-retentive suspend");
+    common::exit(0);
+}
@@ -0,0 +1,42 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::hart_state_management::{
+    hart_state, hart_stop, wait_for_started, wait_for_stopped, HartState,
+};
+use sbi::SbiError;
+
+extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(
+        sbi::hart_state_management::wait_for_state(usize::MAX, HartState::Started),
+        Err(SbiError::INVALID_PARAMETER),
+        "❌ waited on an invalid hart ID"
+    );
+    println!("🆗 waiting on an invalid hart ID rejected");
+
+    let target_hart = if hart_id == 0 { 1 } else { 0 };
+    common::start_other_hart(other_main);
+    let start = common::time();
+    wait_for_started(target_hart).expect("wait_for_started");
+    assert!(
+        common::time() - start < 10_000_000,
+        "❌ Hart {target_hart} took too long to start"
+    );
+    println!("🆗 Hart {target_hart} started");
+
+    wait_for_stopped(target_hart).expect("wait_for_stopped");
+    assert_eq!(hart_state(target_hart), Ok(HartState::Stopped));
+    println!("🆗 Hart {target_hart} stopped");
+
+    common::exit(0);
+}
+
+#[no_mangle]
+extern "C" fn other_main(_: usize) -> ! {
+    common::wait(10);
+    #[allow(unreachable_code)]
+    match hart_stop().expect("hart_stop") {}
+}
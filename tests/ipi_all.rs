@@ -0,0 +1,74 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// Far enough from the real harts that the hart IDs span more than
+// `usize::BITS` IDs and must be split into multiple hart masks
+const DISTANT_HART: usize = 200;
+
+// Keeps the receiving hart from exiting before the signaled count is checked
+static COUNT_CHECKED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
+    let target_hart = if hart_id == 0 { 1 } else { 0 };
+    common::start_other_hart(other_main);
+    sbi::hart_state_management::wait_for_started(target_hart).expect("wait_for_started");
+
+    let mut windows = sbi::HartMask::windows([DISTANT_HART, target_hart].into_iter());
+    assert_eq!(windows.next(), Some(sbi::HartMask::from(target_hart)));
+    assert_eq!(windows.next(), Some(sbi::HartMask::from(DISTANT_HART)));
+
+    assert_eq!(
+        sbi::ipi::send_ipi_to_all([DISTANT_HART]),
+        Err((
+            sbi::SbiError::INVALID_PARAMETER,
+            sbi::HartMask::from(DISTANT_HART)
+        )),
+        "❌ IPI to a nonexistent hart"
+    );
+    println!("🆗 IPI to a nonexistent hart rejected with its hart mask");
+
+    let real_harts = [target_hart, target_hart];
+    let expected: usize = sbi::HartMask::windows(real_harts.into_iter())
+        .map(|hart_mask| hart_mask.raw_mask().count_ones() as usize)
+        .sum();
+    assert_eq!(expected, 1);
+    assert_eq!(
+        sbi::ipi::send_ipi_to_all(real_harts),
+        Ok(expected),
+        "❌ signaled hart count"
+    );
+    COUNT_CHECKED.store(true, Ordering::Release);
+    common::wait(1000);
+    println!("❌ Other hart did not trigger an exit in time");
+    common::exit(1);
+}
+
+#[no_mangle]
+extern "C" fn other_main(_: usize) -> ! {
+    println!("🆗 Hart started");
+    common::set_stvec(success);
+    common::enable_interrupts();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
+
+const SUPERVISOR_SOFTWARE_INTERRUPT: usize = (1 << (usize::BITS - 1)) | 1;
+#[repr(align(4))]
+extern "C" fn success() -> ! {
+    assert_eq!(
+        common::scause(),
+        SUPERVISOR_SOFTWARE_INTERRUPT,
+        "not an IPI"
+    );
+    while !COUNT_CHECKED.load(Ordering::Acquire) {
+        core::hint::spin_loop();
+    }
+    println!("🆗 IPI received -- success");
+    common::exit(0);
+}
@@ -0,0 +1,98 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::fmt::Write;
+use sbi::{hart_state_management::HartState, legacy::LegacyHartMask, HartMask};
+
+extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(
+        LegacyHartMask::new(HartMask::from_ids([0, 1, 3]))
+            .unwrap()
+            .as_slice(),
+        &[0b1011]
+    );
+    assert_eq!(
+        LegacyHartMask::new(HartMask::new(usize::BITS as usize - 1).with(usize::BITS as usize))
+            .unwrap()
+            .as_slice(),
+        &[0, 1]
+    );
+    assert_eq!(
+        LegacyHartMask::new(HartMask::new(usize::BITS as usize - 2).with(usize::BITS as usize - 1))
+            .unwrap()
+            .as_slice(),
+        &[1 << (usize::BITS - 1)]
+    );
+    assert_eq!(
+        LegacyHartMask::new(HartMask::new(5)).unwrap().as_slice(),
+        &[0]
+    );
+    assert!(LegacyHartMask::new(HartMask::from(LegacyHartMask::MAX_HARTS - 1)).is_some());
+    assert!(LegacyHartMask::new(HartMask::from(LegacyHartMask::MAX_HARTS)).is_none());
+    println!("🆗 legacy hart masks successfully converted");
+
+    writeln!(sbi::legacy::LegacyConsole, "🆗 legacy console wrote {}", 42).expect("writeln");
+
+    let mut reader = sbi::legacy::LineReader::<8>::new();
+    let mut input = b"hx\x7Fel".iter().copied();
+    assert_eq!(reader.read_line_from(|| input.next(), drop), None);
+    let mut input = b"lo, world\nnext".iter().copied();
+    assert_eq!(
+        reader.read_line_from(|| input.next(), drop),
+        None,
+        "❌ overflowing line returned"
+    );
+    assert_eq!(reader.read_line_from(|| input.next(), drop), None);
+    let mut input = b"\x08\x08\x08ab\r".iter().copied();
+    assert_eq!(reader.read_line_from(|| input.next(), drop), Some("nab"));
+    let mut input = b"\nok\r\n".iter().copied();
+    assert_eq!(reader.read_line_from(|| input.next(), drop), Some("ok"));
+    assert_eq!(
+        reader.read_line_from(|| input.next(), drop),
+        None,
+        "❌ \\r\\n ended two lines"
+    );
+    println!("🆗 legacy line reader successfully read lines");
+
+    sbi::legacy::remote_fence_i_mask(&LegacyHartMask::new(HartMask::from(hart_id)).unwrap());
+    println!("🆗 legacy remote fence successfully sent");
+
+    let target_hart = if hart_id == 0 { 1 } else { 0 };
+    common::start_other_hart(other_main);
+    while !matches!(
+        sbi::hart_state_management::hart_state(target_hart).expect("hart_status"),
+        HartState::Started
+    ) {
+        common::wait(100);
+    }
+
+    sbi::legacy::send_ipi_mask(&LegacyHartMask::new(HartMask::from(target_hart)).unwrap());
+    common::wait(1000);
+    println!("❌ Other hart did not trigger an exit in time");
+    common::exit(1);
+}
+
+#[no_mangle]
+extern "C" fn other_main(_: usize) -> ! {
+    println!("🆗 Hart started");
+    common::set_stvec(success);
+    common::enable_interrupts();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
+
+const SUPERVISOR_SOFTWARE_INTERRUPT: usize = (1 << (usize::BITS - 1)) | 1;
+#[repr(align(4))]
+extern "C" fn success() -> ! {
+    assert_eq!(
+        common::scause(),
+        SUPERVISOR_SOFTWARE_INTERRUPT,
+        "not an IPI"
+    );
+    println!("🆗 Legacy IPI received -- success");
+    common::exit(0);
+}
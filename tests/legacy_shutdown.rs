@@ -0,0 +1,10 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    println!("🆗 shutting down through the legacy extension");
+    sbi::legacy::shutdown();
+}
@@ -0,0 +1,177 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::mem::MaybeUninit;
+use sbi::nested_acceleration::{
+    csrs::{Hstatus, Vsstatus},
+    AutoswapCsr, AutoswapCsrFeature, AutoswapFlags, CsrAddress, CsrSpace, Gpr, HfenceEntries,
+    HfenceEntry, HfenceType, SharedMemoryLayout, SretScratch, SynchronizeCsr,
+    SynchronizeCsrFeature, SynchronizeHfence, SynchronizeHfenceFeature, SynchronizeSret,
+    SynchronizeSretFeature, NUM_HFENCE_ENTRIES, VALID_CSR_ADDRESS_RANGES,
+};
+use sbi::SbiError;
+
+static mut SHARED_MEMORY: MaybeUninit<SharedMemoryLayout> = MaybeUninit::zeroed();
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let shmem = core::ptr::addr_of_mut!(SHARED_MEMORY).cast::<SharedMemoryLayout>();
+    let csr_space = shmem as usize + 4096;
+    let slot_size = core::mem::size_of::<usize>();
+    assert_eq!(
+        shmem.index::<Hstatus>() as usize,
+        csr_space + 0x100 * slot_size
+    );
+    assert_eq!(shmem.index::<Vsstatus>() as usize, csr_space);
+    for (address, index) in [
+        (0x200, 0x000),
+        (0x2FF, 0x0FF),
+        (0x600, 0x100),
+        (0x680, 0x180),
+        (0x6C0, 0x1C0),
+        (0xA00, 0x200),
+        (0xE00, 0x300),
+        (0xEFF, 0x3FF),
+    ] {
+        assert_eq!(CsrAddress::new(address).unwrap().shmem_index(), index);
+    }
+    for address in [0x000, 0x1FF, 0x300, 0x5FF, 0x700, 0xB00, 0xF00, 0xFFF] {
+        assert_eq!(CsrAddress::new(address), None);
+        assert!(!VALID_CSR_ADDRESS_RANGES
+            .iter()
+            .any(|range| range.contains(&address)));
+    }
+    assert_eq!(CsrAddress::new(0x6C0).unwrap().as_u16(), 0x6C0);
+    assert_eq!(CsrAddress::new_unchecked(0x123).as_u16(), 0x123);
+    for range in VALID_CSR_ADDRESS_RANGES {
+        for address in [*range.start(), *range.end()] {
+            assert_eq!(
+                CsrAddress::new(address).map(CsrAddress::as_u16),
+                Some(address)
+            );
+        }
+    }
+    println!("🆗 CSR slots successfully located");
+
+    unsafe {
+        assert_eq!(shmem.synchronize_sret() as usize, shmem as usize);
+        assert_eq!(shmem.autoswap_csr() as usize, shmem as usize + 0x0200);
+        assert_eq!(shmem.synchronize_hfence() as usize, shmem as usize + 0x0800);
+        assert_eq!(shmem.synchronize_csr() as usize, shmem as usize + 0x0F80);
+    }
+    println!("🆗 feature regions successfully located");
+
+    let hstatus_slot = shmem.index::<Hstatus>().cast::<usize>();
+    unsafe {
+        hstatus_slot.write_volatile(0x1234);
+        let hstatus = shmem.read_csr::<Hstatus>();
+        hstatus_slot.write_volatile(0);
+        shmem.write_csr(hstatus);
+        assert_eq!(hstatus_slot.read_volatile(), 0x1234);
+    }
+    println!("🆗 CSR slots successfully read and written");
+
+    let hstatus = Hstatus::from_bits((1 << 7) | (1 << 6) | (5 << 12) | (1 << 21));
+    assert!(hstatus.spv() && hstatus.gva() && hstatus.vtw());
+    assert!(!hstatus.spvp() && !hstatus.vtsr());
+    assert_eq!(hstatus.vgein(), 5);
+    assert_eq!(Hstatus::from_bits(hstatus.bits()), hstatus);
+    let vsstatus = Vsstatus::from_bits(1 << 1);
+    assert!(vsstatus.sie() && !vsstatus.spie());
+    println!("🆗 CSR fields successfully decoded");
+
+    let mut flags = AutoswapFlags::NONE;
+    assert!(!flags.contains(AutoswapFlags::AUTOSWAP_CSR));
+    flags |= AutoswapFlags::AUTOSWAP_CSR;
+    assert!(flags.contains(AutoswapFlags::AUTOSWAP_CSR));
+    assert_eq!(AutoswapFlags::NONE | AutoswapFlags::AUTOSWAP_CSR, flags);
+    assert_eq!(AutoswapFlags::default(), AutoswapFlags::NONE);
+    println!("🆗 autoswap flags successfully constructed");
+
+    let mut shmem_flags = sbi::nacl::Flags::default();
+    shmem_flags |= sbi::nacl::Flags::NONE;
+    assert_eq!(shmem_flags | sbi::nacl::Flags::NONE, sbi::nacl::Flags::NONE);
+    assert!(shmem_flags.contains(sbi::nacl::Flags::NONE));
+    assert_eq!(shmem_flags.bits(), 0);
+    println!("🆗 shared memory flags successfully constructed");
+
+    let entry = HfenceEntry {
+        pending: true,
+        kind: HfenceType::GvmaVmid,
+        order: 9,
+        vmid: 5,
+        asid: 0,
+        page_number: 0x8_0000,
+        page_count: 16,
+    };
+    unsafe {
+        let hfence = shmem.synchronize_hfence();
+        assert!(hfence.write_entry(0, entry));
+        assert_eq!(hfence.read_entry(0), Some(entry));
+        assert!(!hfence.write_entry(NUM_HFENCE_ENTRIES, entry));
+        assert_eq!(hfence.read_entry(NUM_HFENCE_ENTRIES), None);
+    }
+    println!("🆗 HFENCE entries successfully written and read");
+
+    assert_eq!(Gpr::from_index(10), Some(Gpr::A0));
+    assert_eq!(Gpr::from_index(0), None);
+    assert_eq!(Gpr::from_index(32), None);
+    unsafe {
+        let sret = shmem.synchronize_sret();
+        sret.write_gpr(Gpr::A0, 0xDEAD_BEEF);
+        assert_eq!(sret.read_gpr(Gpr::A0), 0xDEAD_BEEF);
+        assert_eq!(sret.cast::<usize>().add(10).read_volatile(), 0xDEAD_BEEF);
+    }
+    println!("🆗 SRET scratch registers successfully written and read");
+
+    let available = sbi::base::probe_extension(sbi::nacl::EXTENSION_ID).is_available();
+    match sbi::nacl::probe_all_features() {
+        Ok(features) => {
+            assert_eq!(
+                Ok(features.synchronize_csr),
+                sbi::nacl::probe_feature::<SynchronizeCsr>()
+            );
+            assert_eq!(
+                Ok(features.synchronize_hfence),
+                sbi::nacl::probe_feature::<SynchronizeHfence>()
+            );
+            assert_eq!(
+                Ok(features.synchronize_sret),
+                sbi::nacl::probe_feature::<SynchronizeSret>()
+            );
+            assert_eq!(
+                Ok(features.autoswap_csr),
+                sbi::nacl::probe_feature::<AutoswapCsr>()
+            );
+            println!("🆗 features successfully probed: {features:?}");
+        }
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 nested acceleration not supported, skipping feature probing")
+        }
+        Err(e) => panic!("❌ failed to probe features: {e}"),
+    }
+
+    if !available {
+        assert_eq!(
+            unsafe { sbi::nacl::synchronize_hfence(sbi::nacl::UPDATE_ALL_HFENCE_ENTRIES) },
+            Err(SbiError::NOT_SUPPORTED)
+        );
+        assert_eq!(
+            unsafe { sbi::nacl::synchronize_sret() },
+            Err(SbiError::NOT_SUPPORTED)
+        );
+        println!("🆗 nested acceleration not supported, synchronization calls rejected");
+    }
+
+    match sbi::nacl::disable_shared_memory(sbi::nacl::Flags::NONE) {
+        Ok(()) => println!("🆗 shared memory successfully disabled"),
+        Err(SbiError::NOT_SUPPORTED) if !available => {
+            println!("🆗 nested acceleration not supported, skipping")
+        }
+        Err(e) => panic!("❌ failed to disable shared memory: {e}"),
+    }
+
+    common::exit(0);
+}
@@ -0,0 +1,36 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::PhysicalAddress;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let mut buffer = [0u32; 12];
+    let address = PhysicalAddress::from_ptr(&mut buffer[2..9] as *mut [u32]);
+    assert_eq!(address.as_ptr(), buffer[2..].as_mut_ptr());
+    assert_eq!(address.len(), 7);
+
+    let null = PhysicalAddress::from_ptr(core::ptr::slice_from_raw_parts_mut(
+        core::ptr::null_mut::<u8>(),
+        16,
+    ));
+    assert!(null.as_ptr().is_null());
+    assert_eq!(null.len(), 16);
+    println!("🆗 slice physical addresses successfully decomposed");
+
+    let address = PhysicalAddress::<u64>::new(0x8000_0000);
+    assert_eq!(address.as_ptr() as usize, 0x8000_0000);
+    println!("🆗 sized physical addresses successfully decomposed");
+
+    let bytes = address.cast::<u8>();
+    assert_eq!(bytes.as_ptr() as usize, 0x8000_0000);
+    assert_eq!(bytes.cast::<u64>(), address);
+    let mut buffer = [0u32; 4];
+    let slice = PhysicalAddress::from_ptr(&mut buffer[1..] as *mut [u32]);
+    assert_eq!(slice.cast::<u32>().as_ptr(), slice.as_ptr());
+    println!("🆗 physical addresses successfully cast");
+
+    common::exit(0);
+}
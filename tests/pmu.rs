@@ -0,0 +1,306 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::mem::MaybeUninit;
+use sbi::{
+    pmu::{
+        CounterConfigurationFlags, CounterIndex, CounterIndexMask, CounterInfo, CounterSession,
+        CounterStartFlags, CounterStopFlags, EventCode, EventIndex, EventType, FirmwareEvent,
+        FirmwareEventCode, HardwareCacheEvent, HardwareCacheEventCode, SnapshotFlags,
+        SnapshotSharedMemory,
+    },
+    PhysicalAddress, RestrictedRange, SbiError,
+};
+
+static mut SNAPSHOT: MaybeUninit<SnapshotSharedMemory> = MaybeUninit::zeroed();
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let (event_idx, event_data) = EventIndex::raw(0xDEAD_BEEF);
+    assert_eq!(event_idx, EventIndex::from_raw(2, 0), "❌ raw event index");
+    assert_eq!(event_data, 0xDEAD_BEEF, "❌ raw event data");
+    println!("🆗 raw event encoded");
+
+    assert_eq!(
+        EventIndex::new(FirmwareEvent, FirmwareEventCode::Platform),
+        EventIndex::from_raw(0xF, 0xFFFF),
+        "❌ platform firmware event index"
+    );
+    assert_eq!(
+        EventIndex::new(
+            FirmwareEvent,
+            FirmwareEventCode::SbiImplementationSpecific(RestrictedRange::new(0x1234))
+        ),
+        EventIndex::from_raw(0xF, 0x1234),
+        "❌ SBI implementation specific firmware event index"
+    );
+    println!("🆗 non-standard firmware events encoded");
+
+    let cache_code = HardwareCacheEventCode::LAST_LEVEL_WRITE_MISS;
+    let cache_event = EventIndex::new(HardwareCacheEvent, cache_code);
+    assert_eq!(
+        cache_event.event_type_value(),
+        HardwareCacheEvent::TYPE_VALUE
+    );
+    assert_eq!(cache_event.event_code(), cache_code.to_code());
+    assert_eq!(EventIndex::from_bits(cache_event.bits()), cache_event);
+
+    use sbi::pmu::{
+        HardwareCacheEventCodeId as CacheId, HardwareCacheEventCodeOperationId as CacheOp,
+        HardwareCacheEventCodeResultId as CacheResult,
+    };
+    assert_eq!(cache_code.to_code(), (2 << 3) | (1 << 1) | 1);
+    assert_eq!(
+        cache_code.parts(),
+        (CacheId::LastLevel, CacheOp::Write, CacheResult::Miss)
+    );
+    for id in [
+        CacheId::Level1Data,
+        CacheId::Level1Instruction,
+        CacheId::LastLevel,
+        CacheId::DataTlb,
+        CacheId::InstructionTlb,
+        CacheId::BranchPredictorUnit,
+        CacheId::NumaNode,
+    ] {
+        for op in [CacheOp::Read, CacheOp::Write, CacheOp::Prefetch] {
+            for result in [CacheResult::Access, CacheResult::Miss] {
+                let code = HardwareCacheEventCode::new(id, op, result);
+                assert_eq!(code.parts(), (id, op, result));
+                assert_eq!(
+                    HardwareCacheEventCode::from_code(code.to_code()),
+                    Some(code)
+                );
+            }
+        }
+    }
+    assert_eq!(HardwareCacheEventCode::from_code(7 << 3), None);
+    assert_eq!(HardwareCacheEventCode::from_code(3 << 1), None);
+    println!("🆗 hardware cache event codes round tripped");
+
+    let firmware_event = EventIndex::new(FirmwareEvent, FirmwareEventCode::Platform);
+    assert_eq!(firmware_event.event_type_value(), FirmwareEvent::TYPE_VALUE);
+    assert_eq!(firmware_event.event_code(), 0xFFFF);
+    assert_eq!(EventIndex::from_bits(firmware_event.bits()), firmware_event);
+    println!("🆗 event indices decoded");
+
+    let inhibit = CounterConfigurationFlags::SET_SINH | CounterConfigurationFlags::SET_MINH;
+    assert_eq!(
+        inhibit.bits().count_ones(),
+        2,
+        "❌ mode inhibit flags overlap"
+    );
+    assert_eq!(CounterConfigurationFlags::SET_SINH.bits(), 1 << 6);
+    assert_eq!(CounterConfigurationFlags::SET_MINH.bits(), 1 << 7);
+    assert!(inhibit.contains(CounterConfigurationFlags::SET_MINH));
+    assert!(!inhibit.contains(CounterConfigurationFlags::SET_UINH));
+    println!("🆗 mode inhibit flags are distinct");
+
+    let start_flags = CounterStartFlags::SET_INIT_VALUE;
+    let copied = start_flags;
+    assert_eq!(start_flags, copied);
+    assert_ne!(start_flags, CounterStartFlags::default());
+    let mut stop_flags = CounterStopFlags::NONE;
+    stop_flags |= CounterStopFlags::RESET;
+    let copied = stop_flags;
+    assert_eq!(stop_flags, copied);
+    assert_eq!(
+        stop_flags,
+        CounterStopFlags::default() | CounterStopFlags::RESET
+    );
+    println!("🆗 start and stop flags copied and compared");
+
+    let mask = CounterIndexMask::new(CounterIndex::new(4))
+        .with(CounterIndex::new(4))
+        .with(CounterIndex::new(10));
+    assert_eq!(mask.count(), 2);
+    assert_eq!(mask.base(), 4);
+    assert_eq!(mask.raw_mask(), 1 | (1 << 6));
+    let single = CounterIndexMask::from(CounterIndex::new(7));
+    assert_eq!((single.base(), single.raw_mask()), (7, 1));
+    assert!(mask.contains(CounterIndex::new(10)));
+    assert!(!mask.contains(CounterIndex::new(3)));
+    assert!(mask
+        .iter()
+        .eq([CounterIndex::new(4), CounterIndex::new(10)]));
+    let last = CounterIndex::new(4 + usize::BITS as usize - 1);
+    assert!(mask.try_with(last).is_some_and(|mask| mask.contains(last)));
+    assert_eq!(
+        mask.try_with(CounterIndex::new(4 + usize::BITS as usize)),
+        None
+    );
+    assert_eq!(mask.try_with(CounterIndex::new(3)), None);
+    println!("🆗 counter index mask selection");
+
+    assert_eq!(sbi::counter_mask!(4, 10), mask);
+    assert_eq!(sbi::counter_mask!(base: 4, ids: 4, 10), mask);
+    assert_eq!(
+        sbi::counter_mask!(0, 2, 4),
+        CounterIndexMask::from(CounterIndex::new(0))
+            .with(CounterIndex::new(2))
+            .with(CounterIndex::new(4))
+    );
+    assert_eq!(
+        sbi::counter_mask!(base: 0, ids: 1, 3),
+        CounterIndexMask::new(CounterIndex::new(0))
+            .with(CounterIndex::new(1))
+            .with(CounterIndex::new(3))
+    );
+    println!("🆗 counter index masks constructed with counter_mask!");
+
+    let bits = usize::BITS as usize;
+    let mut windows = CounterIndexMask::covering(2 * bits + 2);
+    assert_eq!(
+        windows.next().map(|m| (m.base(), m.count())),
+        Some((0, bits))
+    );
+    assert_eq!(
+        windows.next().map(|m| (m.base(), m.count())),
+        Some((bits, bits))
+    );
+    assert_eq!(
+        windows.next().map(|m| (m.base(), m.raw_mask())),
+        Some((2 * bits, 0b11))
+    );
+    assert_eq!(windows.next(), None);
+    assert_eq!(CounterIndexMask::covering(bits).count(), 1);
+    assert_eq!(CounterIndexMask::covering(0).count(), 0);
+    println!("🆗 counter index masks covering all counters");
+
+    let hardware = CounterInfo::from_raw(0xC03 | (63 << 12) | (0b1010 << 20));
+    assert_eq!(
+        hardware,
+        CounterInfo::Hardware {
+            csr_number: 0xC03,
+            width: 63
+        }
+    );
+    assert_eq!(hardware.width_bits(), 64);
+    let firmware = CounterInfo::from_raw((1 << (usize::BITS - 1)) | (63 << 12) | 0xFFF);
+    assert_eq!(firmware, CounterInfo::Firmware { width: 63 });
+    assert_eq!(firmware.width_bits(), 64);
+    println!("🆗 counter info decoding");
+
+    assert!(
+        sbi::pmu::counters().any(|(_, info)| matches!(info, Ok(CounterInfo::Firmware { .. }))),
+        "❌ no firmware counters found"
+    );
+    println!("🆗 firmware counters enumerated");
+
+    let mut all_counters = CounterIndexMask::new(CounterIndex::new(0));
+    for idx in 0..sbi::pmu::num_counters() {
+        all_counters = all_counters.with(CounterIndex::new(idx));
+    }
+
+    let mut session = CounterSession::new(
+        all_counters,
+        CounterConfigurationFlags::CLEAR_VALUE,
+        EventIndex::new(FirmwareEvent, FirmwareEventCode::SetTimer),
+        0,
+    )
+    .expect("configure firmware counter");
+    assert!(matches!(
+        session.counter_info(),
+        CounterInfo::Firmware { .. }
+    ));
+    session.start().expect("start firmware counter");
+    for _ in 0..5 {
+        sbi::timer::clear().expect("clear");
+    }
+    session.stop().expect("stop firmware counter");
+    assert_eq!(session.read(), Ok(5), "❌ firmware counter value");
+    let session_counter = session.counter_index();
+    drop(session);
+    let session_mask = CounterIndexMask::from(session_counter);
+    assert_eq!(
+        sbi::pmu::configure_matching_counters(
+            session_mask,
+            CounterConfigurationFlags::NONE,
+            EventIndex::new(FirmwareEvent, FirmwareEventCode::SetTimer),
+            0,
+        ),
+        Ok(session_counter),
+        "❌ dropped session did not release its counter"
+    );
+    let _ = sbi::pmu::stop_and_reset_counters(session_mask);
+    println!("🆗 firmware counter session lifecycle");
+
+    let counter = sbi::pmu::configure_matching_counters(
+        all_counters,
+        CounterConfigurationFlags::CLEAR_VALUE | CounterConfigurationFlags::AUTO_START,
+        EventIndex::new(FirmwareEvent, FirmwareEventCode::SetTimer),
+        0,
+    )
+    .expect("configure firmware counter");
+    for _ in 0..3 {
+        sbi::timer::clear().expect("clear");
+    }
+    sbi::pmu::stop_counters(CounterIndexMask::from(counter), CounterStopFlags::NONE)
+        .expect("stop firmware counter");
+    let value = sbi::pmu::read_firmware_counter_u64(counter).expect("read firmware counter");
+    assert_eq!(value, 3, "❌ 64-bit firmware counter value");
+    #[cfg(target_arch = "riscv64")]
+    assert_eq!(
+        sbi::pmu::read_firmware_counter(counter).map(|value| value as u64),
+        Ok(value),
+        "❌ 64-bit firmware counter read differs from the native read"
+    );
+    let _ = sbi::pmu::stop_and_reset_counters(CounterIndexMask::from(counter));
+    println!("🆗 firmware counter read as 64 bits");
+
+    let set_timer = EventIndex::new(FirmwareEvent, FirmwareEventCode::SetTimer);
+    let counter = sbi::pmu::configure_matching_counters(
+        all_counters,
+        CounterConfigurationFlags::CLEAR_VALUE | CounterConfigurationFlags::AUTO_START,
+        set_timer,
+        0,
+    )
+    .expect("configure firmware counter");
+    let counter_mask = CounterIndexMask::from(counter);
+    sbi::pmu::stop_and_reset_counters(counter_mask).expect("stop and reset counter");
+    assert_eq!(
+        sbi::pmu::configure_matching_counters(
+            counter_mask,
+            CounterConfigurationFlags::AUTO_START,
+            set_timer,
+            0,
+        ),
+        Ok(counter),
+        "❌ reset counter was not freed"
+    );
+    sbi::pmu::stop_and_reset_counters(counter_mask).expect("stop and reset counter");
+    println!("🆗 counters stopped and reset");
+
+    let num_counters = sbi::pmu::num_counters();
+    assert_eq!(
+        sbi::pmu::counter_info_checked(CounterIndex::new(num_counters)),
+        Err(SbiError::INVALID_PARAMETER),
+        "❌ out of range counter index"
+    );
+    assert_eq!(
+        sbi::pmu::counter_info_checked(CounterIndex::new(0)),
+        sbi::pmu::counter_info(CounterIndex::new(0)),
+    );
+    println!("🆗 counter indices checked against the number of counters");
+
+    let snapshot = core::ptr::addr_of_mut!(SNAPSHOT).cast::<SnapshotSharedMemory>();
+    match unsafe {
+        sbi::pmu::set_snapshot_shared_memory(
+            PhysicalAddress::from_ptr(snapshot),
+            PhysicalAddress::new(0),
+            SnapshotFlags::NONE,
+        )
+    } {
+        Ok(()) => {
+            sbi::pmu::disable_snapshot_shared_memory(SnapshotFlags::NONE)
+                .expect("disable snapshot shared memory");
+            println!("🆗 snapshot shared memory set and disabled");
+        }
+        Err(SbiError::NOT_SUPPORTED) => println!("🆗 snapshots not supported, skipping"),
+        Err(e) => panic!("❌ failed to set snapshot shared memory: {e}"),
+    }
+
+    common::exit(0);
+}
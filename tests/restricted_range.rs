@@ -0,0 +1,27 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::{hsm::SuspendType, system_reset::ResetType, RestrictedRange};
+
+const PLATFORM_SUSPEND: SuspendType = SuspendType::PlatformSpecificRetentive(
+    sbi::restricted_range!(RestrictedRange<0x10000000, 0x7FFFFFFF>, 0x10000001),
+);
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let SuspendType::PlatformSpecificRetentive(value) = PLATFORM_SUSPEND else {
+        panic!("❌ wrong suspend type");
+    };
+    assert_eq!(u32::from(value), 0x10000001);
+
+    let reset = ResetType::PlatformSpecific(sbi::restricted_range!(
+        RestrictedRange<0xF0000000, 0xFFFFFFFF>,
+        0xFFFFFFFF,
+    ));
+    assert_eq!(u32::from(reset), 0xFFFFFFFF);
+    println!("🆗 restricted ranges successfully checked at compile time");
+
+    common::exit(0);
+}
@@ -0,0 +1,42 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::HartMask;
+
+extern "C" fn main(hart_id: usize, _fdt: usize) -> ! {
+    let hart_ids = [130, 1, 70, 0, 64, 1];
+    let mut windows = HartMask::windows(hart_ids.iter().copied());
+    assert_eq!(windows.next(), Some(HartMask::from_ids([0, 1])));
+    assert_eq!(windows.next(), Some(HartMask::from_ids([64, 70])));
+    assert_eq!(windows.next(), Some(HartMask::from(130)));
+    assert_eq!(windows.next(), None);
+    assert_eq!(HartMask::windows(core::iter::empty()).next(), None);
+    println!("🆗 hart IDs successfully partitioned");
+
+    sbi::rfence::remote_fence_i_all([hart_id].into_iter()).expect("remote_fence_i_all");
+    sbi::rfence::remote_sfence_vma_all([hart_id].into_iter(), 0, usize::MAX)
+        .expect("remote_sfence_vma_all");
+    sbi::rfence::remote_sfence_vma_asid(
+        HartMask::from(hart_id),
+        0,
+        usize::MAX,
+        sbi::rfence::Asid::new(0),
+    )
+    .expect("remote_sfence_vma_asid");
+    println!("🆗 remote fences successfully executed");
+
+    let invalid_base = 10 * usize::BITS as usize;
+    let (_, unfenced) =
+        sbi::rfence::remote_fence_i_all([invalid_base + 3, hart_id, invalid_base].into_iter())
+            .expect_err("fence on nonexistent harts succeeded");
+    assert_eq!(
+        unfenced,
+        HartMask::from_ids([invalid_base, invalid_base + 3])
+    );
+    println!("🆗 unfenced harts successfully reported");
+
+    common::exit(0);
+}
@@ -0,0 +1,28 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::SbiError;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(SbiError::from_raw(-3), SbiError::INVALID_PARAMETER);
+    assert_eq!(SbiError::INVALID_PARAMETER.as_raw(), -3);
+    assert_eq!(SbiError::from_raw(-42).as_raw(), -42);
+    assert_eq!(SbiError::from_raw(-9), SbiError::SHARED_MEMORY_UNAVAILABLE);
+    println!("🆗 raw error codes successfully converted");
+
+    assert!(common::displays_as(SbiError::FAILED, "call to SBI failed"));
+    assert!(common::displays_as(
+        SbiError::SHARED_MEMORY_UNAVAILABLE,
+        "shared memory is unavailable"
+    ));
+    assert!(common::displays_as(
+        SbiError::from_raw(-42),
+        "unknown SBI error (-42)"
+    ));
+    println!("🆗 errors successfully displayed");
+
+    common::exit(0);
+}
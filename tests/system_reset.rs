@@ -0,0 +1,79 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use core::convert::Infallible;
+use sbi::system_reset::{ResetReason, ResetType};
+use sbi::{ReservedValue, RestrictedRange, SbiError};
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let _: fn() -> Result<Infallible, SbiError> = sbi::system_reset::cold_reboot;
+    let _: fn() -> Result<Infallible, SbiError> = sbi::system_reset::warm_reboot;
+    let _: fn() -> Result<Infallible, SbiError> = sbi::system_reset::shutdown;
+
+    assert_eq!(ResetType::try_from(0), Ok(ResetType::Shutdown));
+    assert_eq!(ResetType::try_from(1), Ok(ResetType::ColdReboot));
+    assert_eq!(ResetType::try_from(2), Ok(ResetType::WarmReboot));
+    assert_eq!(
+        ResetType::try_from(0xF000_0001),
+        Ok(ResetType::PlatformSpecific(RestrictedRange::new(
+            0xF000_0001
+        )))
+    );
+    assert_eq!(
+        ResetType::try_from(3),
+        Err(ReservedValue { value: 3 }),
+        "❌ reserved reset type"
+    );
+    assert_eq!(ResetReason::try_from(0), Ok(ResetReason::NoReason));
+    assert_eq!(ResetReason::try_from(1), Ok(ResetReason::SystemFailure));
+    assert_eq!(
+        ResetReason::try_from(0xE000_0000),
+        Ok(ResetReason::SbiSpecific(RestrictedRange::new(0xE000_0000)))
+    );
+    assert_eq!(
+        ResetReason::try_from(0xFFFF_FFFF),
+        Ok(ResetReason::PlatformSpecific(RestrictedRange::new(
+            0xFFFF_FFFF
+        )))
+    );
+    assert_eq!(
+        ResetReason::try_from(0x8000_0000),
+        Err(ReservedValue { value: 0x8000_0000 }),
+        "❌ reserved reset reason"
+    );
+    println!("🆗 reset types and reasons successfully decoded");
+
+    for (kind, name) in [
+        (ResetType::Shutdown, "shutdown"),
+        (ResetType::ColdReboot, "cold-reboot"),
+        (ResetType::WarmReboot, "warm-reboot"),
+        (
+            ResetType::PlatformSpecific(RestrictedRange::new(0xF000_0001)),
+            "0xF0000001",
+        ),
+    ] {
+        assert!(common::displays_as(kind, name));
+        assert_eq!(name.parse::<ResetType>(), Ok(kind));
+    }
+    for (reason, name) in [
+        (ResetReason::NoReason, "no-reason"),
+        (ResetReason::SystemFailure, "system-failure"),
+        (
+            ResetReason::SbiSpecific(RestrictedRange::new(0xE000_0000)),
+            "0xE0000000",
+        ),
+    ] {
+        assert!(common::displays_as(reason, name));
+        assert_eq!(name.parse::<ResetReason>(), Ok(reason));
+    }
+    assert!("reboot".parse::<ResetType>().is_err());
+    assert!("0x3".parse::<ResetType>().is_err());
+    assert!("0x80000000".parse::<ResetReason>().is_err());
+    println!("🆗 reset types and reasons successfully parsed and formatted");
+
+    println!("🆗 shutting down");
+    sbi::system_reset::shutdown_or_legacy();
+}
@@ -0,0 +1,32 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+use sbi::system_suspend::SleepType;
+use sbi::{ReservedValue, RestrictedRange};
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert_eq!(SleepType::try_from(0), Ok(SleepType::SuspendToRam));
+    assert_eq!(
+        SleepType::try_from(0x8000_0000),
+        Ok(SleepType::PlatformSpecific(RestrictedRange::new(
+            0x8000_0000
+        )))
+    );
+    assert_eq!(
+        SleepType::try_from(1),
+        Err(ReservedValue { value: 1 }),
+        "❌ reserved sleep type"
+    );
+    for sleep_type in [0, 0x8000_0000, 0xFFFF_FFFF] {
+        assert_eq!(
+            SleepType::try_from(sleep_type).map(u32::from),
+            Ok(sleep_type)
+        );
+    }
+    println!("🆗 sleep types successfully decoded");
+
+    common::exit(0);
+}
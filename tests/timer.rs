@@ -7,7 +7,7 @@ mod common;
 extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
     common::set_stvec(success);
     common::enable_interrupts();
-    sbi::timer::set_timer(common::time() + 100).expect("set_timer");
+    sbi::timer::set_timer(sbi::timer::Deadline::at(common::time() + 100)).expect("set_timer");
     common::wait(100);
     common::exit(1);
 }
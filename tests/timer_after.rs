@@ -0,0 +1,35 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    let now = sbi::timer::now();
+    assert!(sbi::timer::now() >= now);
+    assert_eq!(sbi::timer::Deadline::at(now).absolute(), now);
+    assert!(sbi::timer::Deadline::after(100).absolute() >= now + 100);
+    assert!(sbi::timer::Deadline::at(now) < sbi::timer::Deadline::after(100));
+    assert_eq!(
+        sbi::timer::Deadline::after(u64::MAX),
+        sbi::timer::Deadline::at(u64::MAX)
+    );
+    common::set_stvec(success);
+    common::enable_interrupts();
+    sbi::timer::set_timer_after(100).expect("set_timer_after");
+    common::wait(100);
+    common::exit(1);
+}
+
+const SUPERVISOR_TIMER_INTERRUPT: usize = (1 << (usize::BITS - 1)) | 5;
+
+#[repr(align(4))]
+extern "C" fn success() -> ! {
+    assert_eq!(
+        common::scause(),
+        SUPERVISOR_TIMER_INTERRUPT,
+        "was not a timer interrupt!"
+    );
+    println!("🆗 Relative timer interrupt received -- success");
+    common::exit(0);
+}
@@ -0,0 +1,24 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    common::set_stvec(failure);
+    common::enable_interrupts();
+    sbi::timer::set_timer_after(1000).expect("set_timer_after");
+    sbi::timer::clear().expect("clear");
+    common::wait(10);
+    println!("🆗 No timer interrupt received after clearing -- success");
+    common::exit(0);
+}
+
+#[repr(align(4))]
+extern "C" fn failure() -> ! {
+    println!(
+        "Unexpected trap after clearing the timer: {:#X}",
+        common::scause()
+    );
+    common::exit(1);
+}
@@ -0,0 +1,36 @@
+#![feature(naked_functions, fn_align)]
+#![no_std]
+#![no_main]
+
+mod common;
+
+// Only the high 32 bits are set, so that on RV32 the deadline is far in the
+// future if the low word is passed in `a0` and the high word in `a1`, but is
+// immediately in the past (and raises a timer interrupt) if they are swapped
+const DEADLINE: u64 = 1 << 32;
+
+extern "C" fn main(_hart_id: usize, _fdt: usize) -> ! {
+    assert!(common::time() < DEADLINE, "❌ deadline is in the past");
+    common::set_stvec(failure);
+    common::enable_interrupts();
+
+    sbi::timer::set_timer_absolute(DEADLINE).expect("set_timer_absolute");
+    common::wait(10);
+    println!("🆗 No timer interrupt received from set_timer_absolute -- success");
+
+    sbi::legacy::set_timer(DEADLINE);
+    common::wait(10);
+    println!("🆗 No timer interrupt received from legacy set_timer -- success");
+
+    sbi::timer::clear().expect("clear");
+    common::exit(0);
+}
+
+#[repr(align(4))]
+extern "C" fn failure() -> ! {
+    println!(
+        "Unexpected trap, timer value words were swapped: {:#X}",
+        common::scause()
+    );
+    common::exit(1);
+}
@@ -0,0 +1,6 @@
+// error-pattern: invalid value supplied to `PlatformSpecific::new`
+#![no_std]
+
+pub fn out_of_range() -> sbi::RestrictedRange<0x10000000, 0x7FFFFFFF> {
+    sbi::restricted_range!(sbi::RestrictedRange<0x10000000, 0x7FFFFFFF>, 0x80000000)
+}
@@ -0,0 +1,7 @@
+// error-pattern: unused return value of `sbi::rfence::remote_fence_i` that must be used
+#![no_std]
+#![deny(unused_must_use)]
+
+pub fn ignore_result() {
+    sbi::rfence::remote_fence_i(sbi::HartMask::from(0));
+}
@@ -0,0 +1,35 @@
+#!/bin/sh
+# Compile-fail checks. The crate only builds for RISC-V targets, so these can't
+# be `compile_fail` doctests: on any other target every example fails on the
+# crate's `compile_error!`. Instead, each `.rs` file in this directory is built
+# as a library against the crate for a RISC-V target, and must fail with the
+# message on its `// error-pattern:` line.
+#
+# Run from the repository root: `tests/ui/run.sh [target]`
+set -eu
+
+target="${1:-riscv64imac-unknown-none-elf}"
+cargo build --lib --quiet --target "$target"
+
+deps="target/$target/debug"
+out=$(mktemp -d)
+trap 'rm -rf "$out"' EXIT
+
+status=0
+for file in tests/ui/*.rs; do
+    pattern=$(sed -n 's|^// error-pattern: ||p' "$file")
+    if output=$(rustc --edition 2021 --crate-type lib --target "$target" \
+        --emit=obj --out-dir "$out" -L "dependency=$deps/deps" \
+        --extern "sbi=$deps/libsbi.rlib" "$file" 2>&1); then
+        echo "❌ $file compiled"
+        status=1
+    elif printf '%s\n' "$output" | grep -qF -- "$pattern"; then
+        echo "🆗 $file failed to compile"
+    else
+        echo "❌ $file failed to compile with an unexpected error:"
+        printf '%s\n' "$output"
+        status=1
+    fi
+done
+
+exit "$status"